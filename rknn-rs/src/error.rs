@@ -1,16 +1,284 @@
+use crate::prelude::RknnTensorType;
+use rknn_sys_rs as rknn_sys;
+use std::path::PathBuf;
+
+/// Error code returned by the RKNN API, see the `RKNN_ERR_*` defines in `rknn_api.h`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum RknnErrorCode {
+    /// `RKNN_ERR_FAIL`: execute failed.
+    Fail,
+    /// `RKNN_ERR_TIMEOUT`: execute timeout.
+    Timeout,
+    /// `RKNN_ERR_DEVICE_UNAVAILABLE`: device is unavailable.
+    DeviceUnavailable,
+    /// `RKNN_ERR_MALLOC_FAIL`: memory malloc fail.
+    MallocFail,
+    /// `RKNN_ERR_PARAM_INVALID`: parameter is invalid.
+    ParamInvalid,
+    /// `RKNN_ERR_MODEL_INVALID`: model is invalid.
+    ModelInvalid,
+    /// `RKNN_ERR_CTX_INVALID`: context is invalid.
+    CtxInvalid,
+    /// `RKNN_ERR_INPUT_INVALID`: input is invalid.
+    InputInvalid,
+    /// `RKNN_ERR_OUTPUT_INVALID`: output is invalid.
+    OutputInvalid,
+    /// `RKNN_ERR_DEVICE_UNMATCH`: the device does not match, update the rknn sdk and npu driver/firmware.
+    DeviceUnmatch,
+    /// `RKNN_ERR_INCOMPATILE_PRE_COMPILE_MODEL`: pre_compile model not compatible with the current driver.
+    IncompatiblePreCompileModel,
+    /// `RKNN_ERR_INCOMPATILE_OPTIMIZATION_LEVEL_VERSION`: optimization level not compatible with the current driver.
+    IncompatibleOptimizationLevelVersion,
+    /// `RKNN_ERR_TARGET_PLATFORM_UNMATCH`: model target platform not compatible with the current platform.
+    TargetPlatformUnmatch,
+    /// A code not defined in `rknn_api.h`.
+    Unknown(i32),
+}
+
+impl RknnErrorCode {
+    /// Convert a raw return value of the RKNN API to an error code.
+    pub fn from_int(code: i32) -> Self {
+        match code {
+            rknn_sys::RKNN_ERR_FAIL => RknnErrorCode::Fail,
+            rknn_sys::RKNN_ERR_TIMEOUT => RknnErrorCode::Timeout,
+            rknn_sys::RKNN_ERR_DEVICE_UNAVAILABLE => RknnErrorCode::DeviceUnavailable,
+            rknn_sys::RKNN_ERR_MALLOC_FAIL => RknnErrorCode::MallocFail,
+            rknn_sys::RKNN_ERR_PARAM_INVALID => RknnErrorCode::ParamInvalid,
+            rknn_sys::RKNN_ERR_MODEL_INVALID => RknnErrorCode::ModelInvalid,
+            rknn_sys::RKNN_ERR_CTX_INVALID => RknnErrorCode::CtxInvalid,
+            rknn_sys::RKNN_ERR_INPUT_INVALID => RknnErrorCode::InputInvalid,
+            rknn_sys::RKNN_ERR_OUTPUT_INVALID => RknnErrorCode::OutputInvalid,
+            rknn_sys::RKNN_ERR_DEVICE_UNMATCH => RknnErrorCode::DeviceUnmatch,
+            rknn_sys::RKNN_ERR_INCOMPATILE_PRE_COMPILE_MODEL => {
+                RknnErrorCode::IncompatiblePreCompileModel
+            }
+            rknn_sys::RKNN_ERR_INCOMPATILE_OPTIMIZATION_LEVEL_VERSION => {
+                RknnErrorCode::IncompatibleOptimizationLevelVersion
+            }
+            rknn_sys::RKNN_ERR_TARGET_PLATFORM_UNMATCH => RknnErrorCode::TargetPlatformUnmatch,
+            other => RknnErrorCode::Unknown(other),
+        }
+    }
+
+    /// The raw `RKNN_ERR_*` value.
+    pub fn code(self) -> i32 {
+        match self {
+            RknnErrorCode::Fail => rknn_sys::RKNN_ERR_FAIL,
+            RknnErrorCode::Timeout => rknn_sys::RKNN_ERR_TIMEOUT,
+            RknnErrorCode::DeviceUnavailable => rknn_sys::RKNN_ERR_DEVICE_UNAVAILABLE,
+            RknnErrorCode::MallocFail => rknn_sys::RKNN_ERR_MALLOC_FAIL,
+            RknnErrorCode::ParamInvalid => rknn_sys::RKNN_ERR_PARAM_INVALID,
+            RknnErrorCode::ModelInvalid => rknn_sys::RKNN_ERR_MODEL_INVALID,
+            RknnErrorCode::CtxInvalid => rknn_sys::RKNN_ERR_CTX_INVALID,
+            RknnErrorCode::InputInvalid => rknn_sys::RKNN_ERR_INPUT_INVALID,
+            RknnErrorCode::OutputInvalid => rknn_sys::RKNN_ERR_OUTPUT_INVALID,
+            RknnErrorCode::DeviceUnmatch => rknn_sys::RKNN_ERR_DEVICE_UNMATCH,
+            RknnErrorCode::IncompatiblePreCompileModel => {
+                rknn_sys::RKNN_ERR_INCOMPATILE_PRE_COMPILE_MODEL
+            }
+            RknnErrorCode::IncompatibleOptimizationLevelVersion => {
+                rknn_sys::RKNN_ERR_INCOMPATILE_OPTIMIZATION_LEVEL_VERSION
+            }
+            RknnErrorCode::TargetPlatformUnmatch => rknn_sys::RKNN_ERR_TARGET_PLATFORM_UNMATCH,
+            RknnErrorCode::Unknown(code) => code,
+        }
+    }
+}
+
+impl std::fmt::Display for RknnErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            RknnErrorCode::Fail => "RKNN_ERR_FAIL",
+            RknnErrorCode::Timeout => "RKNN_ERR_TIMEOUT",
+            RknnErrorCode::DeviceUnavailable => "RKNN_ERR_DEVICE_UNAVAILABLE",
+            RknnErrorCode::MallocFail => "RKNN_ERR_MALLOC_FAIL",
+            RknnErrorCode::ParamInvalid => "RKNN_ERR_PARAM_INVALID",
+            RknnErrorCode::ModelInvalid => "RKNN_ERR_MODEL_INVALID",
+            RknnErrorCode::CtxInvalid => "RKNN_ERR_CTX_INVALID",
+            RknnErrorCode::InputInvalid => "RKNN_ERR_INPUT_INVALID",
+            RknnErrorCode::OutputInvalid => "RKNN_ERR_OUTPUT_INVALID",
+            RknnErrorCode::DeviceUnmatch => "RKNN_ERR_DEVICE_UNMATCH",
+            RknnErrorCode::IncompatiblePreCompileModel => "RKNN_ERR_INCOMPATILE_PRE_COMPILE_MODEL",
+            RknnErrorCode::IncompatibleOptimizationLevelVersion => {
+                "RKNN_ERR_INCOMPATILE_OPTIMIZATION_LEVEL_VERSION"
+            }
+            RknnErrorCode::TargetPlatformUnmatch => "RKNN_ERR_TARGET_PLATFORM_UNMATCH",
+            RknnErrorCode::Unknown(_) => "unknown error",
+        };
+        write!(f, "{}", name)
+    }
+}
+
 /// Error type for RKNN operations.
 #[derive(Debug)]
-pub struct Error(pub String);
+#[non_exhaustive]
+pub enum Error {
+    /// An RKNN API call returned a non-zero code.
+    Api {
+        /// The failing API, e.g. `"rknn_init"`.
+        api: &'static str,
+        /// The raw return value.
+        code: i32,
+        /// `code` decoded as an `RKNN_ERR_*` value.
+        kind: RknnErrorCode,
+    },
+    /// An RKNN allocation API returned a null pointer.
+    AllocationFailed {
+        /// The failing API, e.g. `"rknn_create_mem"`.
+        api: &'static str,
+    },
+    /// A buffer that must be valid is null or has been released.
+    NullBuffer(String),
+    /// An input or output index is out of range.
+    IndexOutOfRange {
+        /// `"input"` or `"output"`.
+        kind: &'static str,
+        index: usize,
+        count: usize,
+    },
+    /// No input or output tensor has the requested name.
+    UnknownTensorName {
+        /// `"input"` or `"output"`.
+        kind: &'static str,
+        name: String,
+        /// The names the model does have, in index order.
+        valid: Vec<String>,
+    },
+    /// An input buffer does not match the tensor it is bound to.
+    InputSizeMismatch {
+        /// The tensor name.
+        name: String,
+        /// The tensor's current dimensions.
+        dims: Vec<u32>,
+        /// Expected number of elements.
+        expected: usize,
+        /// Number of elements in the buffer, in the declared type.
+        actual: usize,
+    },
+    /// An array does not have the shape of the tensor it is bound to.
+    ShapeMismatch {
+        /// The tensor name.
+        name: String,
+        expected: Vec<usize>,
+        actual: Vec<usize>,
+    },
+    /// A buffer does not have the expected size.
+    SizeMismatch {
+        /// What was being checked.
+        what: String,
+        expected: usize,
+        actual: usize,
+    },
+    /// A Rust element type does not match the tensor type.
+    TypeMismatch {
+        /// What was being checked.
+        what: String,
+        expected: RknnTensorType,
+        actual: RknnTensorType,
+    },
+    /// A byte buffer cannot be viewed as the requested element type.
+    InvalidCast {
+        /// The requested element type.
+        type_name: &'static str,
+    },
+    /// Incompatible or unsupported configuration, e.g. conflicting init flags.
+    InvalidConfig(String),
+    /// An argument was rejected before calling the runtime.
+    InvalidArgument(String),
+    /// The model's custom string could not be deserialized.
+    InvalidMetadata(String),
+    /// Reading a model file failed.
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+impl Error {
+    /// Create an [`Error::Api`] from the return value of `api`.
+    pub fn api(api: &'static str, code: i32) -> Self {
+        Error::Api {
+            api,
+            code,
+            kind: RknnErrorCode::from_int(code),
+        }
+    }
+
+    /// The RKNN error code, if this error was returned by the runtime.
+    pub fn code(&self) -> Option<RknnErrorCode> {
+        match self {
+            Error::Api { kind, .. } => Some(*kind),
+            _ => None,
+        }
+    }
+}
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+        match self {
+            Error::Api { api, code, kind } => {
+                write!(f, "{} failed: {} (exit code:{})", api, kind, code)
+            }
+            Error::AllocationFailed { api } => write!(f, "{} failed.", api),
+            Error::NullBuffer(msg) => write!(f, "{}", msg),
+            Error::IndexOutOfRange { kind, index, count } => write!(
+                f,
+                "{} index {} out of range (model has {} {}s)",
+                kind, index, count, kind
+            ),
+            Error::UnknownTensorName { kind, name, valid } => write!(
+                f,
+                "No {} named '{}' (valid names: {})",
+                kind,
+                name,
+                valid.join(", ")
+            ),
+            Error::InputSizeMismatch {
+                name,
+                dims,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Input '{}' with dims {:?} expects {} elements, got {}",
+                name, dims, expected, actual
+            ),
+            Error::ShapeMismatch {
+                name,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Tensor '{}': expected shape {:?}, got {:?}",
+                name, expected, actual
+            ),
+            Error::SizeMismatch {
+                what,
+                expected,
+                actual,
+            } => write!(f, "{}: expected {}, got {}", what, expected, actual),
+            Error::TypeMismatch {
+                what,
+                expected,
+                actual,
+            } => write!(f, "{}: expected {:?}, got {:?}", what, expected, actual),
+            Error::InvalidCast { type_name } => {
+                write!(f, "Tensor memory cannot be viewed as {}", type_name)
+            }
+            Error::InvalidConfig(msg) => write!(f, "{}", msg),
+            Error::InvalidArgument(msg) => write!(f, "{}", msg),
+            Error::InvalidMetadata(msg) => write!(f, "Invalid custom metadata: {}", msg),
+            Error::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+        }
     }
 }
 
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        None
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
     }
 
     fn description(&self) -> &str {
@@ -24,10 +292,7 @@ impl std::error::Error for Error {
 
 #[macro_export]
 macro_rules! rkerr {
-    ($msg:expr, $code:expr) => {
-        Err($crate::error::Error(format!(
-            "{} exit code:{}",
-            $msg, $code
-        )))
+    ($api:expr, $code:expr) => {
+        Err($crate::error::Error::api($api, $code))
     };
 }
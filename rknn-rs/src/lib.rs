@@ -11,16 +11,28 @@ pub mod prelude {
     use super::rknn_sys;
     use std::{
         ffi::CString,
+        fs::File,
+        io::{Seek, SeekFrom},
         marker::PhantomData,
         mem,
+        os::fd::{AsRawFd, BorrowedFd, RawFd},
         os::raw::{c_char, c_void},
+        path::{Path, PathBuf},
         ptr::{self, null_mut, NonNull},
         slice,
+        sync::Arc,
+        time::Duration,
     };
 
-    pub use crate::error::Error;
+    pub use crate::error::{Error, RknnErrorCode};
     use crate::rkerr;
     use bytemuck::Pod;
+    #[cfg(feature = "half")]
+    use half::slice::HalfFloatSliceExt;
+    #[cfg(feature = "half")]
+    pub use half::{bf16, f16};
+    #[cfg(feature = "ndarray")]
+    use ndarray::{ArrayView, ArrayViewD, Dimension, IxDyn};
 
     fn c_char_array_to_string(chars: &[c_char]) -> String {
         let end = chars.iter().position(|&c| c == 0).unwrap_or(chars.len());
@@ -45,13 +57,13 @@ pub mod prelude {
         pub fmt: RknnTensorFormat,
     }
 
-    impl<T> Default for RknnInput<T> {
+    impl<T: TensorElement> Default for RknnInput<T> {
         fn default() -> Self {
             Self {
                 index: Default::default(),
                 buf: Default::default(),
                 pass_through: Default::default(),
-                type_: RknnTensorType::Float32,
+                type_: T::TYPE,
                 fmt: RknnTensorFormat::Undefined,
             }
         }
@@ -60,7 +72,7 @@ pub mod prelude {
     /// RKNN tensor type.
     ///
     /// This enum defines the supported tensor data types in an RKNN model.
-    #[derive(Debug, Copy, Clone)]
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
     pub enum RknnTensorType {
         /// 32-bit floating point.
         Float32 = 0,
@@ -108,6 +120,137 @@ pub mod prelude {
                 _ => RknnTensorType::TypeMax,
             }
         }
+
+        /// Size of one element in bits, or `None` for `TypeMax`.
+        pub fn size_in_bits(self) -> Option<usize> {
+            match self {
+                RknnTensorType::Int4 => Some(4),
+                RknnTensorType::Int8 | RknnTensorType::Uint8 | RknnTensorType::Boolean => Some(8),
+                RknnTensorType::Float16
+                | RknnTensorType::BFloat16
+                | RknnTensorType::Int16
+                | RknnTensorType::Uint16 => Some(16),
+                RknnTensorType::Float32 | RknnTensorType::Int32 | RknnTensorType::Uint32 => {
+                    Some(32)
+                }
+                RknnTensorType::Int64 => Some(64),
+                RknnTensorType::TypeMax => None,
+            }
+        }
+    }
+
+    mod sealed {
+        pub trait Sealed {}
+    }
+
+    /// Whether a buffer of `element` can hold a tensor of type `tensor`.
+    ///
+    /// Besides an exact match, `u8` reads `Boolean` and packed `Int4` tensors, and `u16`
+    /// reads the bits of `Float16`/`BFloat16` tensors.
+    fn element_matches(tensor: RknnTensorType, element: RknnTensorType) -> bool {
+        use RknnTensorType::*;
+        tensor == element
+            || matches!(
+                (tensor, element),
+                (Boolean | Int4, Uint8) | (Float16 | BFloat16, Uint16)
+            )
+    }
+
+    /// A Rust type that can be used as a tensor element, with its RKNN tensor type.
+    ///
+    /// Implemented for `f32`, `i8`, `u8`, `i16`, `u16`, `i32`, `u32` and `i64`, and for
+    /// `f16`/`bf16` with the `half` feature. This trait is sealed.
+    pub trait TensorElement: Pod + sealed::Sealed {
+        /// The tensor type whose elements have the layout of `Self`.
+        const TYPE: RknnTensorType;
+    }
+
+    macro_rules! tensor_element {
+        ($($t:ty => $type_:ident),* $(,)?) => {
+            $(
+                impl sealed::Sealed for $t {}
+                impl TensorElement for $t {
+                    const TYPE: RknnTensorType = RknnTensorType::$type_;
+                }
+            )*
+        };
+    }
+
+    tensor_element! {
+        f32 => Float32,
+        i8 => Int8,
+        u8 => Uint8,
+        i16 => Int16,
+        u16 => Uint16,
+        i32 => Int32,
+        u32 => Uint32,
+        i64 => Int64,
+    }
+
+    /// An integer tensor element that can hold quantized values: `i8`, `u8` and `i16`.
+    pub trait QuantizedElement: TensorElement {
+        /// Widen to `i32`.
+        fn to_i32(self) -> i32;
+        /// Narrow from `i32`, saturating at the bounds of `Self`.
+        fn from_i32_saturating(value: i32) -> Self;
+    }
+
+    macro_rules! quantized_element {
+        ($($t:ty),*) => {
+            $(
+                impl QuantizedElement for $t {
+                    fn to_i32(self) -> i32 {
+                        self as i32
+                    }
+
+                    fn from_i32_saturating(value: i32) -> Self {
+                        value.clamp(<$t>::MIN as i32, <$t>::MAX as i32) as $t
+                    }
+                }
+            )*
+        };
+    }
+
+    quantized_element!(i8, u8, i16);
+
+    #[cfg(feature = "half")]
+    tensor_element! {
+        f16 => Float16,
+        bf16 => BFloat16,
+    }
+
+    /// Convert `f32` values to `f16`, e.g. to prepare a pass_through FP16 input.
+    ///
+    /// Uses the hardware conversion instructions where available.
+    #[cfg(feature = "half")]
+    pub fn f32_to_f16(src: &[f32]) -> Vec<f16> {
+        let mut dst = vec![f16::ZERO; src.len()];
+        dst.convert_from_f32_slice(src);
+        dst
+    }
+
+    /// Convert `f16` values to `f32`.
+    #[cfg(feature = "half")]
+    pub fn f16_to_f32(src: &[f16]) -> Vec<f32> {
+        let mut dst = vec![0.0; src.len()];
+        src.convert_to_f32_slice(&mut dst);
+        dst
+    }
+
+    /// Convert `f32` values to `bf16`.
+    #[cfg(feature = "half")]
+    pub fn f32_to_bf16(src: &[f32]) -> Vec<bf16> {
+        let mut dst = vec![bf16::ZERO; src.len()];
+        dst.convert_from_f32_slice(src);
+        dst
+    }
+
+    /// Convert `bf16` values to `f32`.
+    #[cfg(feature = "half")]
+    pub fn bf16_to_f32(src: &[bf16]) -> Vec<f32> {
+        let mut dst = vec![0.0; src.len()];
+        src.convert_to_f32_slice(&mut dst);
+        dst
     }
 
     /// RKNN tensor format.
@@ -230,6 +373,57 @@ pub mod prelude {
         }
     }
 
+    /// Quantization parameters of a tensor, taken from its [`RknnTensorAttr`].
+    #[derive(Debug, Copy, Clone)]
+    pub struct RknnQuantParams {
+        pub qnt_type: RknnTensorQntType,
+        /// Zero point (affine).
+        pub zp: i32,
+        /// Scale (affine).
+        pub scale: f32,
+        /// Fractional length (DFP).
+        pub fl: i8,
+    }
+
+    impl RknnQuantParams {
+        pub fn from_attr(attr: &RknnTensorAttr) -> Self {
+            RknnQuantParams {
+                qnt_type: attr.qnt_type,
+                zp: attr.zp,
+                scale: attr.scale,
+                fl: attr.fl,
+            }
+        }
+
+        /// Real value of the quantized value `q`.
+        pub fn dequantize(&self, q: i32) -> f32 {
+            match self.qnt_type {
+                RknnTensorQntType::AffineAsymmetric => (q - self.zp) as f32 * self.scale,
+                RknnTensorQntType::Dfp => q as f32 * 2f32.powi(-(self.fl as i32)),
+                _ => q as f32,
+            }
+        }
+
+        /// Quantize `value`, rounding half to even like the RKNN toolkit. The result is not
+        /// saturated; see [`RknnQuantParams::quantize_to`].
+        pub fn quantize(&self, value: f32) -> i32 {
+            let q = match self.qnt_type {
+                RknnTensorQntType::AffineAsymmetric => {
+                    (value / self.scale).round_ties_even() + self.zp as f32
+                }
+                RknnTensorQntType::Dfp => (value * 2f32.powi(self.fl as i32)).round_ties_even(),
+                _ => value.round_ties_even(),
+            };
+            // Float to int casts saturate at i32::MIN/MAX and map NaN to 0.
+            q as i32
+        }
+
+        /// Quantize `value` to `T`, saturating at the bounds of `T`.
+        pub fn quantize_to<T: QuantizedElement>(&self, value: f32) -> T {
+            T::from_i32_saturating(self.quantize(value))
+        }
+    }
+
     #[derive(Debug, Clone)]
     pub struct RknnSdkVersion {
         pub api_version: String,
@@ -249,6 +443,204 @@ pub mod prelude {
         pub output_attrs: Vec<RknnTensorAttr>,
     }
 
+    /// Memory usage of a context (`RKNN_QUERY_MEM_SIZE`), in bytes.
+    #[derive(Debug, Copy, Clone)]
+    pub struct RknnMemSize {
+        /// Weight memory of the model.
+        pub total_weight_size: u32,
+        /// Internal memory, excluding inputs and outputs.
+        pub total_internal_size: u32,
+        /// Total DMA memory allocated by the context.
+        pub total_dma_allocated_size: u64,
+        /// System SRAM reserved for RKNN.
+        pub total_sram_size: u32,
+        /// Free part of the system SRAM reserved for RKNN.
+        pub free_sram_size: u32,
+    }
+
+    impl std::fmt::Display for RknnMemSize {
+        /// Single-line summary in bytes, e.g. for health checks and logs.
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(
+                f,
+                "weight={} internal={} dma_allocated={} sram_free={}/{}",
+                self.total_weight_size,
+                self.total_internal_size,
+                self.total_dma_allocated_size,
+                self.free_sram_size,
+                self.total_sram_size
+            )
+        }
+    }
+
+    /// Shapes accepted by one input of a dynamic-shape model
+    /// (`RKNN_QUERY_INPUT_DYNAMIC_RANGE`).
+    #[derive(Debug, Clone)]
+    pub struct RknnInputRange {
+        pub index: u32,
+        pub name: String,
+        /// Layout the shapes are expressed in.
+        pub fmt: RknnTensorFormat,
+        pub n_dims: u32,
+        /// Supported shapes, each with `n_dims` dimensions. Shape `k` of every input
+        /// together forms the `k`-th shape set the model was compiled for.
+        pub shapes: Vec<Vec<u32>>,
+    }
+
+    impl From<&rknn_sys::_rknn_input_range> for RknnInputRange {
+        fn from(raw: &rknn_sys::_rknn_input_range) -> Self {
+            let n_dims = raw.n_dims.min(rknn_sys::RKNN_MAX_DIMS) as usize;
+            let shape_number = raw.shape_number.min(rknn_sys::RKNN_MAX_DYNAMIC_SHAPE_NUM) as usize;
+            RknnInputRange {
+                index: raw.index,
+                name: c_char_array_to_string(&raw.name),
+                fmt: RknnTensorFormat::from_int(raw.fmt),
+                n_dims: raw.n_dims,
+                shapes: raw.dyn_range[..shape_number]
+                    .iter()
+                    .map(|dims| dims[..n_dims].to_vec())
+                    .collect(),
+            }
+        }
+    }
+
+    /// One output of [`Rknn::run_bucketed`], as float32 in row-major order.
+    #[derive(Debug, Clone)]
+    pub struct RknnBucketedOutput {
+        /// Dimensions of `data` after cropping.
+        pub dims: Vec<u32>,
+        pub data: Vec<f32>,
+    }
+
+    /// Result of [`Rknn::run_bucketed`].
+    #[derive(Debug, Clone)]
+    pub struct RknnBucketedRun {
+        /// Index of the shape set that was selected.
+        pub shape_set: usize,
+        /// The padded shape of every input, as passed to `rknn_set_input_shapes`.
+        pub input_shapes: Vec<Vec<u32>>,
+        pub outputs: Vec<RknnBucketedOutput>,
+    }
+
+    /// One row of the per-layer table returned by `RKNN_QUERY_PERF_DETAIL`.
+    ///
+    /// Columns that are missing from the runtime's table, or printed as `\`, are left
+    /// empty / `None`.
+    #[derive(Debug, Clone, Default)]
+    pub struct LayerPerf {
+        pub id: u32,
+        pub op_type: String,
+        pub data_type: String,
+        /// Device the layer ran on, e.g. `NPU` or `CPU`.
+        pub target: String,
+        /// Shapes as printed by the runtime, e.g. `(1,3,640,640),(16,3,3,3),(16)`.
+        pub input_shape: String,
+        pub output_shape: String,
+        pub time_us: u64,
+        /// MAC usage in percent, one value per core (`7.14/7.14/7.14` on multi-core
+        /// runs). Empty if not reported.
+        pub mac_usage: Vec<f32>,
+        pub rw_kb: Option<f32>,
+        pub full_name: String,
+    }
+
+    /// Result of `RKNN_QUERY_PERF_DETAIL`.
+    #[derive(Debug, Clone, Default)]
+    pub struct RknnPerfDetail {
+        /// Parsed per-layer rows, in the order printed by the runtime.
+        pub layers: Vec<LayerPerf>,
+        /// The "Total Operator Elapsed ... Time(us)" line, if present.
+        pub total_time_us: Option<u64>,
+        /// The unparsed text, e.g. for logging.
+        pub raw: String,
+    }
+
+    impl RknnPerfDetail {
+        /// Parse the text table printed by the runtime.
+        ///
+        /// Columns are located by the header row (`ID OpType DataType Target ...`), so
+        /// columns may be missing or reordered. Multi-word headers of older runtimes, such
+        /// as `DDR Cycles` or `Task Number`, are recognised by their trailing `Cycles` /
+        /// `Number` word. Rows whose cell count does not match the header are skipped;
+        /// they remain available in `raw`.
+        pub fn parse(text: &str) -> Self {
+            let mut columns: Vec<String> = Vec::new();
+            let mut layers = Vec::new();
+            let mut total_time_us = None;
+            for line in text.lines() {
+                let line = line.trim();
+                if line.starts_with("ID") && line.contains("OpType") {
+                    columns = Self::parse_header(line);
+                    continue;
+                }
+                if line.starts_with("Total") && line.contains("Time(us)") {
+                    total_time_us = line.rsplit(':').next().and_then(|v| v.trim().parse().ok());
+                    continue;
+                }
+                if columns.is_empty() || !line.starts_with(|c: char| c.is_ascii_digit()) {
+                    continue;
+                }
+                let mut tokens: Vec<&str> = line.split_whitespace().collect();
+                let full_name_last = columns.last().is_some_and(|c| c == "FullName");
+                if tokens.len() > columns.len() && full_name_last {
+                    // Only the trailing FullName column may contain spaces.
+                    let last = tokens.split_off(columns.len() - 1).join(" ");
+                    let mut layer = Self::parse_row(&columns, &tokens);
+                    layer.full_name = last;
+                    layers.push(layer);
+                } else if tokens.len() == columns.len() {
+                    layers.push(Self::parse_row(&columns, &tokens));
+                }
+            }
+            RknnPerfDetail {
+                layers,
+                total_time_us,
+                raw: text.to_string(),
+            }
+        }
+
+        fn parse_header(line: &str) -> Vec<String> {
+            let mut columns: Vec<String> = Vec::new();
+            for word in line.split_whitespace() {
+                match columns.last_mut() {
+                    Some(last) if word == "Cycles" || word == "Number" => {
+                        last.push(' ');
+                        last.push_str(word);
+                    }
+                    _ => columns.push(word.to_string()),
+                }
+            }
+            columns
+        }
+
+        fn parse_row(columns: &[String], tokens: &[&str]) -> LayerPerf {
+            let mut layer = LayerPerf::default();
+            for (column, &value) in columns.iter().zip(tokens) {
+                let text = if value == "\\" { "" } else { value };
+                match column.as_str() {
+                    "ID" => layer.id = text.parse().unwrap_or_default(),
+                    "OpType" => layer.op_type = text.to_string(),
+                    "DataType" => layer.data_type = text.to_string(),
+                    "Target" => layer.target = text.to_string(),
+                    "InputShape" => layer.input_shape = text.to_string(),
+                    "OutputShape" => layer.output_shape = text.to_string(),
+                    "Time(us)" => layer.time_us = text.parse().unwrap_or_default(),
+                    "MacUsage(%)" => {
+                        layer.mac_usage = text
+                            .split('/')
+                            .map(str::parse)
+                            .collect::<Result<_, _>>()
+                            .unwrap_or_default()
+                    }
+                    "RW(KB)" => layer.rw_kb = text.parse().ok(),
+                    "FullName" => layer.full_name = text.to_string(),
+                    _ => {}
+                }
+            }
+            layer
+        }
+    }
+
     #[derive(Debug, Copy, Clone)]
     #[repr(u32)]
     pub enum RknnCoreMask {
@@ -262,6 +654,89 @@ pub mod prelude {
         Undefined = 0x1_0000,
     }
 
+    /// Flags passed to `rknn_init`, see the `RKNN_FLAG_*` defines in `rknn_api.h`.
+    ///
+    /// Flags can be combined with `|`. The priority flags share the two lowest bits,
+    /// so at most one of `PRIOR_MEDIUM` / `PRIOR_LOW` may be set (`PRIOR_HIGH` is 0).
+    #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+    pub struct RknnInitFlags(u32);
+
+    impl RknnInitFlags {
+        pub const PRIOR_HIGH: Self = Self(rknn_sys::RKNN_FLAG_PRIOR_HIGH);
+        pub const PRIOR_MEDIUM: Self = Self(rknn_sys::RKNN_FLAG_PRIOR_MEDIUM);
+        pub const PRIOR_LOW: Self = Self(rknn_sys::RKNN_FLAG_PRIOR_LOW);
+        pub const ASYNC_MASK: Self = Self(rknn_sys::RKNN_FLAG_ASYNC_MASK);
+        pub const COLLECT_PERF_MASK: Self = Self(rknn_sys::RKNN_FLAG_COLLECT_PERF_MASK);
+        pub const MEM_ALLOC_OUTSIDE: Self = Self(rknn_sys::RKNN_FLAG_MEM_ALLOC_OUTSIDE);
+        pub const SHARE_WEIGHT_MEM: Self = Self(rknn_sys::RKNN_FLAG_SHARE_WEIGHT_MEM);
+        pub const FENCE_IN_OUTSIDE: Self = Self(rknn_sys::RKNN_FLAG_FENCE_IN_OUTSIDE);
+        pub const FENCE_OUT_OUTSIDE: Self = Self(rknn_sys::RKNN_FLAG_FENCE_OUT_OUTSIDE);
+        pub const COLLECT_MODEL_INFO_ONLY: Self = Self(rknn_sys::RKNN_FLAG_COLLECT_MODEL_INFO_ONLY);
+        pub const INTERNAL_ALLOC_OUTSIDE: Self = Self(rknn_sys::RKNN_FLAG_INTERNAL_ALLOC_OUTSIDE);
+        pub const EXECUTE_FALLBACK_PRIOR_DEVICE_GPU: Self =
+            Self(rknn_sys::RKNN_FLAG_EXECUTE_FALLBACK_PRIOR_DEVICE_GPU);
+        pub const ENABLE_SRAM: Self = Self(rknn_sys::RKNN_FLAG_ENABLE_SRAM);
+        pub const SHARE_SRAM: Self = Self(rknn_sys::RKNN_FLAG_SHARE_SRAM);
+        pub const DISABLE_PROC_HIGH_PRIORITY: Self =
+            Self(rknn_sys::RKNN_FLAG_DISABLE_PROC_HIGH_PRIORITY);
+        pub const DISABLE_FLUSH_INPUT_MEM_CACHE: Self =
+            Self(rknn_sys::RKNN_FLAG_DISABLE_FLUSH_INPUT_MEM_CACHE);
+        pub const DISABLE_FLUSH_OUTPUT_MEM_CACHE: Self =
+            Self(rknn_sys::RKNN_FLAG_DISABLE_FLUSH_OUTPUT_MEM_CACHE);
+        pub const MODEL_BUFFER_ZERO_COPY: Self = Self(rknn_sys::RKNN_FLAG_MODEL_BUFFER_ZERO_COPY);
+
+        /// No flags set (high priority, synchronous, runtime-managed memory).
+        pub const fn empty() -> Self {
+            Self(0)
+        }
+
+        /// Build flags from raw `RKNN_FLAG_*` bits without checking them.
+        pub const fn from_bits_retain(bits: u32) -> Self {
+            Self(bits)
+        }
+
+        /// The raw bits passed to `rknn_init`.
+        pub const fn bits(self) -> u32 {
+            self.0
+        }
+
+        /// Whether every bit of `other` is also set in `self`.
+        ///
+        /// Note that `PRIOR_HIGH` is 0 and therefore always contained.
+        pub const fn contains(self, other: Self) -> bool {
+            self.0 & other.0 == other.0
+        }
+
+        pub fn insert(&mut self, other: Self) {
+            self.0 |= other.0;
+        }
+
+        pub fn remove(&mut self, other: Self) {
+            self.0 &= !other.0;
+        }
+
+        pub fn set(&mut self, other: Self, enabled: bool) {
+            if enabled {
+                self.insert(other);
+            } else {
+                self.remove(other);
+            }
+        }
+    }
+
+    impl std::ops::BitOr for RknnInitFlags {
+        type Output = Self;
+        fn bitor(self, rhs: Self) -> Self {
+            Self(self.0 | rhs.0)
+        }
+    }
+
+    impl std::ops::BitOrAssign for RknnInitFlags {
+        fn bitor_assign(&mut self, rhs: Self) {
+            self.0 |= rhs.0;
+        }
+    }
+
     pub struct RknnMemAllocFlags;
     impl RknnMemAllocFlags {
         pub const DEFAULT: u64 = 0;
@@ -299,12 +774,12 @@ pub mod prelude {
     impl<'a> RknnTensorMemory<'a> {
         fn raw_ref(&self) -> Result<&rknn_sys::rknn_tensor_mem, Error> {
             unsafe { self.raw.as_ref() }
-                .ok_or_else(|| Error("RknnTensorMemory has been released.".to_string()))
+                .ok_or_else(|| Error::NullBuffer("RknnTensorMemory has been released.".to_string()))
         }
 
         fn raw_mut_ref(&mut self) -> Result<&mut rknn_sys::rknn_tensor_mem, Error> {
             unsafe { self.raw.as_mut() }
-                .ok_or_else(|| Error("RknnTensorMemory has been released.".to_string()))
+                .ok_or_else(|| Error::NullBuffer("RknnTensorMemory has been released.".to_string()))
         }
 
         fn raw_bytes_ptr(virt_addr: *mut c_void, size: usize) -> Result<*mut u8, Error> {
@@ -312,7 +787,9 @@ pub mod prelude {
                 return Ok(NonNull::<u8>::dangling().as_ptr());
             }
             if virt_addr.is_null() {
-                return Err(Error("Tensor memory points to a null buffer.".to_string()));
+                return Err(Error::NullBuffer(
+                    "Tensor memory points to a null buffer.".to_string(),
+                ));
             }
             Ok(virt_addr as *mut u8)
         }
@@ -341,32 +818,26 @@ pub mod prelude {
 
         pub fn as_slice<T: Pod>(&self) -> Result<&[T], Error> {
             let bytes = self.as_bytes()?;
-            bytemuck::try_cast_slice(bytes).map_err(|_| {
-                Error(format!(
-                    "Tensor memory cannot be viewed as {}",
-                    std::any::type_name::<T>()
-                ))
+            bytemuck::try_cast_slice(bytes).map_err(|_| Error::InvalidCast {
+                type_name: std::any::type_name::<T>(),
             })
         }
 
         pub fn as_mut_slice<T: Pod>(&mut self) -> Result<&mut [T], Error> {
             let bytes = self.as_bytes_mut()?;
-            bytemuck::try_cast_slice_mut(bytes).map_err(|_| {
-                Error(format!(
-                    "Tensor memory cannot be viewed as mutable {}",
-                    std::any::type_name::<T>()
-                ))
+            bytemuck::try_cast_slice_mut(bytes).map_err(|_| Error::InvalidCast {
+                type_name: std::any::type_name::<T>(),
             })
         }
 
         pub fn write_slice<T: Pod>(&mut self, data: &[T]) -> Result<(), Error> {
             let dst = self.as_mut_slice::<T>()?;
             if data.len() > dst.len() {
-                return Err(Error(format!(
-                    "Input data is too large: {} elements > {} elements",
-                    data.len(),
-                    dst.len()
-                )));
+                return Err(Error::SizeMismatch {
+                    what: "Input data is too large for tensor memory (elements)".to_string(),
+                    expected: dst.len(),
+                    actual: data.len(),
+                });
             }
             dst[..data.len()].copy_from_slice(data);
             Ok(())
@@ -381,7 +852,7 @@ pub mod prelude {
                 )
             };
             if result != 0 {
-                return rkerr!("rknn_mem_sync failed.", result);
+                return rkerr!("rknn_mem_sync", result);
             }
             Ok(())
         }
@@ -394,6 +865,8 @@ pub mod prelude {
     pub struct RknnOutput<'a, T> {
         context: rknn_sys::rknn_context,
         memory: &'a [T],
+        // Attributes of this output for the shape it was computed with.
+        attr: &'a RknnTensorAttr,
         // Holds ALL output structs from the rknn_outputs_get call.
         // The RKNN runtime accesses all model outputs regardless of n_outputs,
         // so we must allocate the full array and release it together.
@@ -421,158 +894,1573 @@ pub mod prelude {
         }
     }
 
-    impl<'a, T: std::fmt::Debug> std::fmt::Debug for RknnOutput<'a, T> {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            f.debug_struct("RknnOutput")
-                .field("context", &self.context)
-                .field("memory", &self.memory)
-                .finish()
-        }
-    }
-
-    /// RKNN model.
-    ///
-    /// This struct encapsulates the context of an RKNN model, providing methods to load the model, set inputs, run inference, and retrieve outputs.
-    ///
-    /// # Examples
-    ///
-    /// Here’s a simple example of how to use the `Rknn` struct:
-    ///
-    /// ```no_run
-    /// use std::path::Path;
-    /// use rknn_rs::prelude::*;
-    ///
-    /// fn main() -> Result<(), Error> {
-    ///     // Initialize the model
-    ///     let model_path = Path::new("model.rknn");
-    ///     let rknn = Rknn::rknn_init(model_path)?;
-    ///
-    ///     // Set input
-    ///     let input = RknnInput::<f32> {
-    ///         index: 0,
-    ///         buf: vec![0.0; 100],
-    ///         pass_through: false,
-    ///         type_: RknnTensorType::Float32,
-    ///         fmt: RknnTensorFormat::NCHW,
-    ///     };
-    ///     rknn.input_set(&input)?;
-    ///
-    ///     // Run the model
-    ///     rknn.run()?;
-    ///
-    ///     // Get output
-    ///     let output = rknn.outputs_get::<f32>()?;
-    ///     println!("Output: {:?}", output);
+    /// All outputs of one inference, see [`Rknn::outputs_get_all`].
     ///
-    ///     Ok(())
-    /// }
-    /// ```
-    #[doc = "Rknn model"]
+    /// Owns the buffers returned by a single `rknn_outputs_get` call and releases them
+    /// together when dropped.
     #[derive(Debug)]
-    pub struct Rknn {
+    pub struct RknnOutputs<'a> {
         context: rknn_sys::rknn_context,
+        all_raws: Vec<rknn_sys::rknn_output>,
+        attrs: &'a [RknnTensorAttr],
     }
 
-    impl Drop for Rknn {
+    impl Drop for RknnOutputs<'_> {
         fn drop(&mut self) {
-            if self.context != 0 {
-                unsafe { rknn_sys::rknn_destroy(self.context) };
+            if !self.all_raws.is_empty() {
+                unsafe {
+                    rknn_sys::rknn_outputs_release(
+                        self.context,
+                        self.all_raws.len() as u32,
+                        self.all_raws.as_mut_ptr(),
+                    );
+                }
             }
         }
     }
-    impl Rknn {
-        /// Initialize an RKNN model.
-        ///
-        /// # Parameters
-        ///
-        /// - `model_path`: The path to the model file.
-        ///
-        /// # Returns
-        ///
-        /// If successful, returns an `Rknn` instance; otherwise, returns an `Error`.
-        #[deprecated(since = "0.2.4", note = "use Rknn::new() instead")]
-        pub fn rknn_init<P: AsRef<std::path::Path>>(model_path: P) -> Result<Self, Error> {
-            let mut ret = Rknn { context: 0 };
-            let path_str = model_path.as_ref().to_string_lossy();
-            let path_cstr = CString::new(path_str.as_ref())
-                .map_err(|e| Error(format!("Invalid model path: {}", e)))?;
 
-            unsafe {
-                let result = rknn_sys::rknn_init(
-                    &mut ret.context,
-                    path_cstr.as_ptr() as *mut std::ffi::c_void,
-                    0,
-                    0,
-                    null_mut(),
-                );
-                if result != 0 {
-                    return rkerr!("rknn_init failed.", result);
-                }
-            }
-            Ok(ret)
+    impl RknnOutputs<'_> {
+        /// Number of outputs.
+        pub fn len(&self) -> usize {
+            self.all_raws.len()
         }
 
-        #[allow(deprecated)]
-        pub fn new<P: AsRef<std::path::Path>>(model_path: P) -> Result<Self, Error> {
-            Self::rknn_init(model_path)
+        pub fn is_empty(&self) -> bool {
+            self.all_raws.is_empty()
         }
 
-        /// Set the model's input.
-        ///
-        /// # Parameters
-        ///
-        /// - `input`: A reference to the generic input structure `RknnInput<T>`.
-        ///
-        /// # Returns
+        /// Zero-copy view of output `index`.
         ///
-        /// If successful, returns `Ok(()`; otherwise, returns an `Error`.
-        pub fn input_set<T: Pod + 'static>(&self, input: &RknnInput<T>) -> Result<(), Error> {
-            self.input_set_slice(
-                input.index,
-                &input.buf,
-                input.pass_through,
-                input.type_,
-                input.fmt,
-            )
+        /// `T` must match the output type, which is `f32` for outputs fetched with
+        /// `want_float`. `Boolean` and `Int4` outputs are read as `u8`, `Float16` and
+        /// `BFloat16` outputs as `u16` (or `f16`/`bf16` with the `half` feature). Use
+        /// [`RknnOutputs::as_bytes`] for untyped access.
+        pub fn get<T: TensorElement>(&self, index: usize) -> Result<RknnOutput<'_, T>, Error> {
+            Ok(RknnOutput {
+                context: self.context,
+                memory: self.slice(index)?,
+                attr: &self.attrs[index],
+                // Released by `self`.
+                all_raws: Vec::new(),
+            })
         }
 
-        pub fn input_set_slice<T: Pod + 'static>(
-            &self,
-            index: usize,
-            buf: &[T],
-            pass_through: bool,
-            type_: RknnTensorType,
-            fmt: RknnTensorFormat,
-        ) -> Result<(), Error> {
-            let total_bytes = (buf.len() * mem::size_of::<T>()) as u32;
-            let mut c_input = rknn_sys::rknn_input {
-                index: index as u32,
-                buf: buf.as_ptr() as *mut c_void,
-                size: total_bytes,
-                pass_through: if pass_through { 1 } else { 0 },
-                type_: type_ as u32,
-                fmt: fmt as u32,
-            };
+        /// Zero-copy view of the output tensor called `name`.
+        pub fn by_name<T: TensorElement>(&self, name: &str) -> Result<RknnOutput<'_, T>, Error> {
+            self.get(tensor_index("output", self.attrs, name)?)
+        }
 
-            let result = unsafe { rknn_sys::rknn_inputs_set(self.context, 1, &mut c_input) };
-            if result != 0 {
-                return rkerr!("rknn_inputs_set failed.", result);
+        /// The raw bytes of output `index`, whatever its type.
+        pub fn as_bytes(&self, index: usize) -> Result<&[u8], Error> {
+            let raw = self.all_raws.get(index).ok_or(Error::IndexOutOfRange {
+                kind: "output",
+                index,
+                count: self.all_raws.len(),
+            })?;
+            if raw.buf.is_null() {
+                return Err(Error::NullBuffer(format!(
+                    "rknn_outputs_get returned null buffer for output index {}",
+                    index
+                )));
             }
-            Ok(())
+            Ok(unsafe { slice::from_raw_parts(raw.buf as *const u8, raw.size as usize) })
         }
 
-        /// Set multiple inputs in a single `rknn_inputs_set` call.
-        ///
-        /// The RKNN 2.3.x runtime may behave incorrectly when inputs are set one-by-one
-        /// (n=1 each call) for models with multiple inputs. Use this method to set all
-        /// inputs atomically in one call when that is the case.
-        ///
-        /// Each element of `inputs` is `(index, raw_bytes, pass_through, type_, fmt)`.
-        /// Use `bytemuck::cast_slice` or `bytemuck::bytes_of` to obtain `&[u8]` from typed slices.
-        pub fn inputs_set_batch(
-            &self,
-            inputs: &[(usize, &[u8], bool, RknnTensorType, RknnTensorFormat)],
-        ) -> Result<(), Error> {
+        fn slice<T: TensorElement>(&self, index: usize) -> Result<&[T], Error> {
+            let raw = self.all_raws.get(index).ok_or(Error::IndexOutOfRange {
+                kind: "output",
+                index,
+                count: self.all_raws.len(),
+            })?;
+            let output_type = if raw.want_float != 0 {
+                RknnTensorType::Float32
+            } else {
+                self.attrs[index].type_
+            };
+            if !element_matches(output_type, T::TYPE) {
+                return Err(Error::TypeMismatch {
+                    what: format!("output {}", index),
+                    expected: output_type,
+                    actual: T::TYPE,
+                });
+            }
+            if raw.buf.is_null() {
+                return Err(Error::NullBuffer(format!(
+                    "rknn_outputs_get returned null buffer for output index {}",
+                    index
+                )));
+            }
+            if !(raw.size as usize).is_multiple_of(mem::size_of::<T>()) {
+                return Err(Error::InvalidCast {
+                    type_name: std::any::type_name::<T>(),
+                });
+            }
+            let num_elements = raw.size as usize / mem::size_of::<T>();
+            Ok(unsafe { slice::from_raw_parts(raw.buf as *const T, num_elements) })
+        }
+    }
+
+    /// A caller-owned output buffer for [`Rknn::outputs_get_into`].
+    #[derive(Debug)]
+    pub struct OutputSlot<'b> {
+        index: usize,
+        want_float: bool,
+        type_: RknnTensorType,
+        buf: &'b mut [u8],
+    }
+
+    impl<'b> OutputSlot<'b> {
+        /// Let the runtime write output `index` into `buf`.
+        ///
+        /// `buf` must hold at least `n_elems` elements of the output; `T` must be `f32` if
+        /// `want_float` is set, or match the output type otherwise (`u8` for `Boolean` and
+        /// `Int4`, `u16` for `Float16`/`BFloat16` are accepted as well).
+        pub fn new<T: TensorElement>(index: usize, buf: &'b mut [T], want_float: bool) -> Self {
+            OutputSlot {
+                index,
+                want_float,
+                type_: T::TYPE,
+                buf: bytemuck::cast_slice_mut(buf),
+            }
+        }
+    }
+
+    /// A raw integer output together with its quantization parameters.
+    ///
+    /// Dequantizes on the CPU instead of asking the runtime for `want_float`, and lets
+    /// postprocessing compare quantized values directly, e.g. against
+    /// [`QuantizedOutput::quantize_threshold`].
+    #[derive(Debug)]
+    pub struct QuantizedOutput<'a, T> {
+        output: RknnOutput<'a, T>,
+        params: RknnQuantParams,
+    }
+
+    impl<'a, T: QuantizedElement> QuantizedOutput<'a, T> {
+        pub fn params(&self) -> RknnQuantParams {
+            self.params
+        }
+
+        /// The underlying raw output.
+        pub fn output(&self) -> &RknnOutput<'a, T> {
+            &self.output
+        }
+
+        /// Dequantized value of element `i`, or `None` if out of range.
+        pub fn get_f32(&self, i: usize) -> Option<f32> {
+            self.output
+                .memory
+                .get(i)
+                .map(|&q| self.params.dequantize(q.to_i32()))
+        }
+
+        /// Dequantize all elements into `dst`, which must have the same length.
+        pub fn dequantize_into(&self, dst: &mut [f32]) -> Result<(), Error> {
+            let src = self.output.memory;
+            if dst.len() != src.len() {
+                return Err(Error::SizeMismatch {
+                    what: format!("elements of output '{}'", self.output.attr.name),
+                    expected: src.len(),
+                    actual: dst.len(),
+                });
+            }
+            match self.params.qnt_type {
+                RknnTensorQntType::AffineAsymmetric => {
+                    let (zp, scale) = (self.params.zp, self.params.scale);
+                    for (d, &q) in dst.iter_mut().zip(src) {
+                        *d = (q.to_i32() - zp) as f32 * scale;
+                    }
+                }
+                _ => {
+                    for (d, &q) in dst.iter_mut().zip(src) {
+                        *d = self.params.dequantize(q.to_i32());
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        /// Dequantize all elements into a new vector.
+        pub fn dequantize(&self) -> Vec<f32> {
+            let mut dst = vec![0.0; self.output.memory.len()];
+            // Lengths match by construction.
+            let _ = self.dequantize_into(&mut dst);
+            dst
+        }
+
+        /// The quantized value of `value`, saturated to `T`, for comparing against raw
+        /// elements without dequantizing them.
+        pub fn quantize_threshold(&self, value: f32) -> T {
+            self.params.quantize_to(value)
+        }
+    }
+
+    impl<T> std::ops::Deref for QuantizedOutput<'_, T> {
+        type Target = [T];
+        fn deref(&self) -> &Self::Target {
+            self.output.memory
+        }
+    }
+
+    impl<'a, T: QuantizedElement> RknnOutput<'a, T> {
+        /// Attach the output's quantization parameters.
+        pub fn into_quantized(self) -> QuantizedOutput<'a, T> {
+            QuantizedOutput {
+                params: RknnQuantParams::from_attr(self.attr),
+                output: self,
+            }
+        }
+    }
+
+    impl<T: TensorElement> RknnOutput<'_, T> {
+        /// The raw bytes of this output.
+        pub fn as_bytes(&self) -> &[u8] {
+            bytemuck::cast_slice(self.memory)
+        }
+    }
+
+    impl<T> RknnOutput<'_, T> {
+        /// Attributes of this output, for the input shapes it was computed with.
+        pub fn attr(&self) -> &RknnTensorAttr {
+            self.attr
+        }
+
+        /// Dimensions of this output, in the order given by [`RknnOutput::format`].
+        pub fn dims(&self) -> &[u32] {
+            &self.attr.dims
+        }
+
+        pub fn format(&self) -> RknnTensorFormat {
+            self.attr.fmt
+        }
+
+        /// The element at a multi-dimensional index, or `None` if it is out of range.
+        ///
+        /// For 4-D NCHW and NHWC outputs and 5-D NC1HWC2 outputs, `index` is the logical
+        /// `[n, c, h, w]` and is mapped to the output's layout. Otherwise it has one entry
+        /// per dimension in [`RknnOutput::dims`] order. (Named `at` so it does not shadow
+        /// `<[T]>::get`.)
+        pub fn at<I: AsRef<[usize]>>(&self, index: I) -> Option<&T> {
+            let index = index.as_ref();
+            let dims: Vec<usize> = self.attr.dims.iter().map(|&d| d as usize).collect();
+            let physical = match (self.attr.fmt, dims.as_slice(), index) {
+                (RknnTensorFormat::NHWC, &[_, _, _, _], &[n, c, h, w]) => vec![n, h, w, c],
+                (RknnTensorFormat::NC1HWC2, &[_, _, _, _, c2], &[n, c, h, w]) if c2 > 0 => {
+                    vec![n, c / c2, h, w, c % c2]
+                }
+                _ => index.to_vec(),
+            };
+            if physical.len() != dims.len() || physical.iter().zip(&dims).any(|(i, d)| i >= d) {
+                return None;
+            }
+            let offset: usize = physical
+                .iter()
+                .zip(row_major_strides(&dims))
+                .map(|(i, s)| i * s)
+                .sum();
+            self.memory.get(offset)
+        }
+    }
+
+    #[cfg(feature = "ndarray")]
+    impl<T> RknnOutput<'_, T> {
+        /// View the output as an n-dimensional array shaped by the output's `dims`,
+        /// without copying.
+        ///
+        /// The axes are in the output's `fmt`, e.g. NCHW.
+        pub fn view(&self) -> Result<ArrayViewD<'_, T>, Error> {
+            let dims: Vec<usize> = self.attr.dims.iter().map(|&d| d as usize).collect();
+            let expected = dims.iter().product();
+            ArrayViewD::from_shape(IxDyn(&dims), self.memory).map_err(|_| Error::SizeMismatch {
+                what: format!("elements of output '{}'", self.attr.name),
+                expected,
+                actual: self.memory.len(),
+            })
+        }
+    }
+
+    impl<'a, T: std::fmt::Debug> std::fmt::Debug for RknnOutput<'a, T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("RknnOutput")
+                .field("context", &self.context)
+                .field("memory", &self.memory)
+                .finish()
+        }
+    }
+
+    /// Where `rknn_init` loads the model from.
+    ///
+    /// Usually created implicitly through `From`, e.g. `builder.build("model.rknn")`
+    /// or `builder.build(include_bytes!("model.rknn"))`.
+    #[derive(Debug)]
+    pub enum RknnModelSource<'a> {
+        /// Path to a `.rknn` file.
+        Path(PathBuf),
+        /// Borrowed model blob. The runtime copies it during init.
+        Bytes(&'a [u8]),
+        /// Owned model blob. The runtime copies it during init, after which it is freed.
+        Owned(Vec<u8>),
+        /// A model stored at `offset..offset + size` inside a larger file. Always loaded
+        /// with `RknnInitFlags::MODEL_BUFFER_ZERO_COPY`, see [`Rknn::from_file_region`].
+        FileRegion {
+            file: RknnModelFile,
+            offset: u64,
+            size: u64,
+        },
+    }
+
+    /// A file containing one or more models, see [`Rknn::from_file_region`].
+    #[derive(Debug, Clone)]
+    pub enum RknnModelFile {
+        /// Path to the file.
+        Path(PathBuf),
+        /// An open file descriptor. The runtime opens it again through `/proc/self/fd`,
+        /// so it only needs to stay open during init.
+        Fd(RawFd),
+    }
+
+    impl RknnModelFile {
+        fn path(&self) -> PathBuf {
+            match self {
+                RknnModelFile::Path(path) => path.clone(),
+                RknnModelFile::Fd(fd) => PathBuf::from(format!("/proc/self/fd/{}", fd)),
+            }
+        }
+    }
+
+    impl From<&Path> for RknnModelFile {
+        fn from(path: &Path) -> Self {
+            RknnModelFile::Path(path.to_path_buf())
+        }
+    }
+
+    impl From<&PathBuf> for RknnModelFile {
+        fn from(path: &PathBuf) -> Self {
+            RknnModelFile::Path(path.clone())
+        }
+    }
+
+    impl From<PathBuf> for RknnModelFile {
+        fn from(path: PathBuf) -> Self {
+            RknnModelFile::Path(path)
+        }
+    }
+
+    impl From<&str> for RknnModelFile {
+        fn from(path: &str) -> Self {
+            RknnModelFile::Path(PathBuf::from(path))
+        }
+    }
+
+    impl From<&File> for RknnModelFile {
+        fn from(file: &File) -> Self {
+            RknnModelFile::Fd(file.as_raw_fd())
+        }
+    }
+
+    impl From<BorrowedFd<'_>> for RknnModelFile {
+        fn from(fd: BorrowedFd<'_>) -> Self {
+            RknnModelFile::Fd(fd.as_raw_fd())
+        }
+    }
+
+    impl<'a> From<&'a Path> for RknnModelSource<'a> {
+        fn from(path: &'a Path) -> Self {
+            RknnModelSource::Path(path.to_path_buf())
+        }
+    }
+
+    impl<'a> From<&'a PathBuf> for RknnModelSource<'a> {
+        fn from(path: &'a PathBuf) -> Self {
+            RknnModelSource::Path(path.clone())
+        }
+    }
+
+    impl From<PathBuf> for RknnModelSource<'_> {
+        fn from(path: PathBuf) -> Self {
+            RknnModelSource::Path(path)
+        }
+    }
+
+    impl<'a> From<&'a str> for RknnModelSource<'a> {
+        fn from(path: &'a str) -> Self {
+            RknnModelSource::Path(PathBuf::from(path))
+        }
+    }
+
+    impl From<String> for RknnModelSource<'_> {
+        fn from(path: String) -> Self {
+            RknnModelSource::Path(PathBuf::from(path))
+        }
+    }
+
+    impl<'a> From<&'a [u8]> for RknnModelSource<'a> {
+        fn from(model: &'a [u8]) -> Self {
+            RknnModelSource::Bytes(model)
+        }
+    }
+
+    impl<'a, const N: usize> From<&'a [u8; N]> for RknnModelSource<'a> {
+        fn from(model: &'a [u8; N]) -> Self {
+            RknnModelSource::Bytes(model)
+        }
+    }
+
+    impl From<Vec<u8>> for RknnModelSource<'_> {
+        fn from(model: Vec<u8>) -> Self {
+            RknnModelSource::Owned(model)
+        }
+    }
+
+    /// Builder for [`Rknn`] exposing every `rknn_init` flag.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rknn_rs::prelude::*;
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let rknn = RknnBuilder::new()
+    ///         .flags(RknnInitFlags::PRIOR_MEDIUM | RknnInitFlags::ENABLE_SRAM)
+    ///         .core_mask(RknnCoreMask::Core0_1_2)
+    ///         .build("model.rknn")?;
+    ///     rknn.run()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[derive(Debug, Clone, Default)]
+    pub struct RknnBuilder {
+        flags: RknnInitFlags,
+        core_mask: Option<RknnCoreMask>,
+        batch_core_num: Option<i32>,
+        weight_source: Option<Arc<RknnContextGuard>>,
+        skip_input_validation: bool,
+    }
+
+    impl RknnBuilder {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Add `flags` to the flags passed to `rknn_init`.
+        pub fn flags(mut self, flags: RknnInitFlags) -> Self {
+            self.flags |= flags;
+            self
+        }
+
+        /// Enable or disable a single flag.
+        pub fn flag(mut self, flag: RknnInitFlags, enabled: bool) -> Self {
+            self.flags.set(flag, enabled);
+            self
+        }
+
+        /// Share the weight memory of `source` (`RKNN_FLAG_SHARE_WEIGHT_MEM`).
+        ///
+        /// The model passed to [`RknnBuilder::build`] must be the same model `source` was
+        /// loaded from. The new context keeps `source` alive until it is dropped itself.
+        pub fn share_weights_with(mut self, source: &Rknn) -> Self {
+            self.flags |= RknnInitFlags::SHARE_WEIGHT_MEM;
+            self.weight_source = Some(Arc::clone(&source.guard));
+            self
+        }
+
+        /// Call [`Rknn::set_core_mask`] right after init.
+        pub fn core_mask(mut self, core_mask: RknnCoreMask) -> Self {
+            self.core_mask = Some(core_mask);
+            self
+        }
+
+        /// Call [`Rknn::set_batch_core_num`] right after init.
+        pub fn batch_core_num(mut self, core_num: i32) -> Self {
+            self.batch_core_num = Some(core_num);
+            self
+        }
+
+        /// Check input buffer sizes against the model before `rknn_inputs_set` (default:
+        /// enabled). See [`Rknn::set_validate_inputs`].
+        pub fn validate_inputs(mut self, enabled: bool) -> Self {
+            self.skip_input_validation = !enabled;
+            self
+        }
+
+        fn init_extend(&self) -> Option<rknn_sys::rknn_init_extend> {
+            let source = self.weight_source.as_ref()?;
+            let mut extend: rknn_sys::rknn_init_extend = unsafe { mem::zeroed() };
+            extend.ctx = source.context;
+            Some(extend)
+        }
+
+        /// Check that `offset..offset + size` lies inside `path` and convert the region
+        /// to the types of `rknn_init_extend`.
+        fn file_region(path: &Path, offset: u64, size: u64) -> Result<(i32, u32), Error> {
+            if size == 0 {
+                return Err(Error::InvalidArgument("Model region is empty.".to_string()));
+            }
+            // Seeking to the end also works for block devices, where metadata().len() is 0.
+            let file_len = File::open(path)
+                .and_then(|mut file| file.seek(SeekFrom::End(0)))
+                .map_err(|source| Error::Io {
+                    path: path.to_path_buf(),
+                    source,
+                })?;
+            let end = offset.checked_add(size).filter(|&end| end <= file_len);
+            let Some(end) = end else {
+                return Err(Error::InvalidArgument(format!(
+                    "Model region {}..{} lies outside {} ({} bytes)",
+                    offset,
+                    offset.saturating_add(size),
+                    path.display(),
+                    file_len
+                )));
+            };
+            let real_offset = i32::try_from(offset).map_err(|_| {
+                Error::InvalidArgument(format!(
+                    "Model region offset {} is too large (max {})",
+                    offset,
+                    i32::MAX
+                ))
+            })?;
+            let real_size = u32::try_from(size).map_err(|_| {
+                Error::InvalidArgument(format!(
+                    "Model region {}..{} is too large: {} bytes > {} bytes",
+                    offset,
+                    end,
+                    size,
+                    u32::MAX
+                ))
+            })?;
+            Ok((real_offset, real_size))
+        }
+
+        fn validate(&self, source: &RknnModelSource<'_>) -> Result<(), Error> {
+            let flags = self.flags;
+            if flags.contains(RknnInitFlags::PRIOR_MEDIUM | RknnInitFlags::PRIOR_LOW) {
+                return Err(Error::InvalidConfig(
+                    "RKNN_FLAG_PRIOR_MEDIUM and RKNN_FLAG_PRIOR_LOW are mutually exclusive."
+                        .to_string(),
+                ));
+            }
+            if flags.contains(RknnInitFlags::SHARE_SRAM)
+                && !flags.contains(RknnInitFlags::ENABLE_SRAM)
+            {
+                return Err(Error::InvalidConfig(
+                    "RKNN_FLAG_SHARE_SRAM requires RKNN_FLAG_ENABLE_SRAM.".to_string(),
+                ));
+            }
+            if flags.contains(RknnInitFlags::SHARE_WEIGHT_MEM) && self.weight_source.is_none() {
+                return Err(Error::InvalidConfig(
+                    "RKNN_FLAG_SHARE_WEIGHT_MEM requires a source context to share weights with."
+                        .to_string(),
+                ));
+            }
+            if flags.contains(RknnInitFlags::COLLECT_MODEL_INFO_ONLY) {
+                return Err(Error::InvalidConfig(
+                    "RKNN_FLAG_COLLECT_MODEL_INFO_ONLY creates a context that cannot run inference; \
+                     use RknnModelInspector instead."
+                        .to_string(),
+                ));
+            }
+            // Flags that need an API this crate does not wrap; a context created with them
+            // could never run inference through `Rknn`.
+            let unsupported = [
+                (
+                    RknnInitFlags::DISABLE_FLUSH_INPUT_MEM_CACHE,
+                    "RKNN_FLAG_DISABLE_FLUSH_INPUT_MEM_CACHE cannot be used with rknn_inputs_set.",
+                ),
+                (
+                    RknnInitFlags::DISABLE_FLUSH_OUTPUT_MEM_CACHE,
+                    "RKNN_FLAG_DISABLE_FLUSH_OUTPUT_MEM_CACHE cannot be used with rknn_outputs_get.",
+                ),
+                (
+                    RknnInitFlags::MEM_ALLOC_OUTSIDE,
+                    "RKNN_FLAG_MEM_ALLOC_OUTSIDE requires rknn_set_internal_mem and \
+                     rknn_set_weight_mem, which are not supported.",
+                ),
+                (
+                    RknnInitFlags::INTERNAL_ALLOC_OUTSIDE,
+                    "RKNN_FLAG_INTERNAL_ALLOC_OUTSIDE requires rknn_set_internal_mem, which is \
+                     not supported.",
+                ),
+                (
+                    RknnInitFlags::FENCE_IN_OUTSIDE,
+                    "RKNN_FLAG_FENCE_IN_OUTSIDE requires passing fence fds to rknn_run, which is \
+                     not supported.",
+                ),
+                (
+                    RknnInitFlags::FENCE_OUT_OUTSIDE,
+                    "RKNN_FLAG_FENCE_OUT_OUTSIDE requires passing fence fds to rknn_run, which is \
+                     not supported.",
+                ),
+            ];
+            if let Some((_, msg)) = unsupported.iter().find(|(f, _)| flags.contains(*f)) {
+                return Err(Error::InvalidConfig(msg.to_string()));
+            }
+            // Zero-copy needs a model buffer allocated by the NPU (`model_buffer_fd`), which
+            // heap memory is not; files are mapped by the runtime itself.
+            if flags.contains(RknnInitFlags::MODEL_BUFFER_ZERO_COPY) {
+                if let RknnModelSource::Bytes(_) | RknnModelSource::Owned(_) = source {
+                    return Err(Error::InvalidConfig(
+                        "RKNN_FLAG_MODEL_BUFFER_ZERO_COPY requires a model buffer allocated by the \
+                         NPU; load the model from a file path instead of a byte buffer."
+                            .to_string(),
+                    ));
+                }
+            }
+            Ok(())
+        }
+
+        /// Initialize an RKNN model with the configured flags.
+        ///
+        /// # Parameters
+        ///
+        /// - `source`: A model path or model blob, see [`RknnModelSource`].
+        ///
+        /// # Returns
+        ///
+        /// If successful, returns an `Rknn` instance; otherwise, returns an `Error`.
+        pub fn build<'a, S: Into<RknnModelSource<'a>>>(&self, source: S) -> Result<Rknn, Error> {
+            let source = source.into();
+            self.validate(&source)?;
+            let mut rknn = Rknn::from_guard(self.init_guard(source, RknnInitFlags::empty())?)?;
+            rknn.validate_inputs = !self.skip_input_validation;
+            if let Some(core_mask) = self.core_mask {
+                rknn.set_core_mask(core_mask)?;
+            }
+            if let Some(core_num) = self.batch_core_num {
+                rknn.set_batch_core_num(core_num)?;
+            }
+            Ok(rknn)
+        }
+
+        /// Call `rknn_init` with the configured flags plus `extra_flags`.
+        fn init_guard(
+            &self,
+            source: RknnModelSource<'_>,
+            extra_flags: RknnInitFlags,
+        ) -> Result<RknnContextGuard, Error> {
+            let mut flags = self.flags | extra_flags;
+            let mut extend = self.init_extend();
+            if let RknnModelSource::FileRegion { file, offset, size } = &source {
+                let (real_offset, real_size) = Self::file_region(&file.path(), *offset, *size)?;
+                // real_model_offset/size are only honored in zero-copy mode.
+                flags |= RknnInitFlags::MODEL_BUFFER_ZERO_COPY;
+                let extend = extend.get_or_insert_with(|| unsafe { mem::zeroed() });
+                extend.real_model_offset = real_offset;
+                extend.real_model_size = real_size;
+            }
+            let flag = flags.bits();
+            let extend_ptr = extend
+                .as_mut()
+                .map_or(null_mut(), |e| e as *mut rknn_sys::rknn_init_extend);
+            let context = match source {
+                RknnModelSource::Path(path) => {
+                    let path_cstr = path_to_cstring(&path)?;
+                    // A size of 0 tells the runtime that `model` is a NUL-terminated file path.
+                    unsafe {
+                        Rknn::init_raw(path_cstr.as_ptr() as *mut c_void, 0, flag, extend_ptr)?
+                    }
+                }
+                RknnModelSource::Bytes(model) => {
+                    let size = model_buffer_size(model)?;
+                    unsafe {
+                        Rknn::init_raw(model.as_ptr() as *mut c_void, size, flag, extend_ptr)?
+                    }
+                }
+                RknnModelSource::Owned(model) => {
+                    let size = model_buffer_size(&model)?;
+                    unsafe {
+                        Rknn::init_raw(model.as_ptr() as *mut c_void, size, flag, extend_ptr)?
+                    }
+                }
+                RknnModelSource::FileRegion { file, .. } => {
+                    let path_cstr = path_to_cstring(&file.path())?;
+                    unsafe {
+                        Rknn::init_raw(path_cstr.as_ptr() as *mut c_void, 0, flag, extend_ptr)?
+                    }
+                }
+            };
+            Ok(RknnContextGuard {
+                context,
+                flags,
+                _source: self.weight_source.clone(),
+            })
+        }
+    }
+
+    fn path_to_cstring(path: &Path) -> Result<CString, Error> {
+        let path_str = path.to_string_lossy();
+        CString::new(path_str.as_ref())
+            .map_err(|e| Error::InvalidArgument(format!("Invalid model path: {}", e)))
+    }
+
+    fn tensor_index(
+        kind: &'static str,
+        attrs: &[RknnTensorAttr],
+        name: &str,
+    ) -> Result<usize, Error> {
+        attrs
+            .iter()
+            .position(|attr| attr.name == name)
+            .ok_or_else(|| Error::UnknownTensorName {
+                kind,
+                name: name.to_string(),
+                valid: attrs.iter().map(|attr| attr.name.clone()).collect(),
+            })
+    }
+
+    fn row_major_strides(dims: &[usize]) -> Vec<usize> {
+        let mut strides = vec![1; dims.len()];
+        for i in (0..dims.len().saturating_sub(1)).rev() {
+            strides[i] = strides[i + 1] * dims[i + 1];
+        }
+        strides
+    }
+
+    /// Copy the leading `region` of the row-major tensor `src` into `dst`.
+    fn copy_region(
+        src: &[f32],
+        src_dims: &[usize],
+        dst: &mut [f32],
+        dst_dims: &[usize],
+        region: &[usize],
+    ) {
+        if region.contains(&0) {
+            return;
+        }
+        let Some((&row, outer)) = region.split_last() else {
+            dst[0] = src[0];
+            return;
+        };
+        let src_strides = row_major_strides(src_dims);
+        let dst_strides = row_major_strides(dst_dims);
+        let mut index = vec![0; outer.len()];
+        loop {
+            let offset = |strides: &[usize]| -> usize {
+                index.iter().zip(strides).map(|(i, s)| i * s).sum()
+            };
+            let (s, d) = (offset(&src_strides), offset(&dst_strides));
+            dst[d..d + row].copy_from_slice(&src[s..s + row]);
+
+            let mut axis = outer.len();
+            loop {
+                if axis == 0 {
+                    return;
+                }
+                axis -= 1;
+                index[axis] += 1;
+                if index[axis] < outer[axis] {
+                    break;
+                }
+                index[axis] = 0;
+            }
+        }
+    }
+
+    fn model_buffer_size(model: &[u8]) -> Result<u32, Error> {
+        // rknn_init treats size == 0 as "model is a path", so an empty buffer
+        // must never reach the runtime.
+        if model.is_empty() {
+            return Err(Error::InvalidArgument("Model buffer is empty.".to_string()));
+        }
+        u32::try_from(model.len()).map_err(|_| {
+            Error::InvalidArgument(format!(
+                "Model buffer is too large: {} bytes > {} bytes",
+                model.len(),
+                u32::MAX
+            ))
+        })
+    }
+
+    /// Query-only handle to an RKNN model.
+    ///
+    /// Created with `RKNN_FLAG_COLLECT_MODEL_INFO_ONLY`, so the runtime parses the model
+    /// without allocating NPU memory for inference. Only query methods are available;
+    /// there is no `run` or `input_set`, so trying to run inference on it does not compile.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rknn_rs::prelude::*;
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let inspector = RknnModelInspector::new("model.rknn")?;
+    ///     let info = inspector.model_info()?;
+    ///     println!("{} inputs, {} outputs", info.io_num.n_input, info.io_num.n_output);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[derive(Debug)]
+    pub struct RknnModelInspector {
+        guard: RknnContextGuard,
+    }
+
+    impl RknnModelInspector {
+        /// Load a model for inspection.
+        ///
+        /// # Parameters
+        ///
+        /// - `source`: A model path or model blob, see [`RknnModelSource`].
+        ///
+        /// # Returns
+        ///
+        /// If successful, returns an `RknnModelInspector`; otherwise, returns an `Error`.
+        pub fn new<'a, S: Into<RknnModelSource<'a>>>(source: S) -> Result<Self, Error> {
+            let guard = RknnBuilder::new()
+                .init_guard(source.into(), RknnInitFlags::COLLECT_MODEL_INFO_ONLY)?;
+            Ok(RknnModelInspector { guard })
+        }
+
+        pub fn sdk_version(&self) -> Result<RknnSdkVersion, Error> {
+            self.guard.sdk_version()
+        }
+
+        pub fn io_num(&self) -> Result<RknnInputOutputNum, Error> {
+            self.guard.io_num()
+        }
+
+        pub fn input_attrs(&self) -> Result<Vec<RknnTensorAttr>, Error> {
+            self.guard.input_attrs()
+        }
+
+        pub fn output_attrs(&self) -> Result<Vec<RknnTensorAttr>, Error> {
+            self.guard.output_attrs()
+        }
+
+        pub fn model_info(&self) -> Result<RknnModelInfo, Error> {
+            self.guard.model_info()
+        }
+
+        /// Weight, internal and DMA memory used by this context.
+        pub fn mem_size(&self) -> Result<RknnMemSize, Error> {
+            self.guard.mem_size()
+        }
+
+        /// The custom string stored in the model at conversion time
+        /// (`RKNN_QUERY_CUSTOM_STRING`), or `None` if the model has none.
+        pub fn custom_string(&self) -> Result<Option<String>, Error> {
+            self.guard.custom_string()
+        }
+
+        /// Deserialize the custom string as JSON, e.g. class labels or normalization
+        /// constants written by the conversion pipeline. Returns `None` if the model has
+        /// no custom string.
+        #[cfg(feature = "serde")]
+        pub fn custom_metadata<T: serde::de::DeserializeOwned>(&self) -> Result<Option<T>, Error> {
+            self.guard.custom_metadata()
+        }
+    }
+
+    /// RKNN model.
+    ///
+    /// This struct encapsulates the context of an RKNN model, providing methods to load the model, set inputs, run inference, and retrieve outputs.
+    ///
+    /// # Examples
+    ///
+    /// Here’s a simple example of how to use the `Rknn` struct:
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use rknn_rs::prelude::*;
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     // Initialize the model
+    ///     let model_path = Path::new("model.rknn");
+    ///     let rknn = Rknn::rknn_init(model_path)?;
+    ///
+    ///     // Set input
+    ///     let input = RknnInput::<f32> {
+    ///         index: 0,
+    ///         buf: vec![0.0; 100],
+    ///         fmt: RknnTensorFormat::NCHW,
+    ///         ..Default::default()
+    ///     };
+    ///     rknn.input_set(&input)?;
+    ///
+    ///     // Run the model
+    ///     rknn.run()?;
+    ///
+    ///     // Get output
+    ///     let output = rknn.outputs_get::<f32>()?;
+    ///     println!("Output: {:?}", output);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[doc = "Rknn model"]
+    #[derive(Debug)]
+    pub struct Rknn {
+        // Copy of `guard.context`, used for all FFI calls.
+        context: rknn_sys::rknn_context,
+        guard: Arc<RknnContextGuard>,
+        // Queried once at construction so that inference never needs `rknn_query`.
+        info: RknnModelInfo,
+        // Current attributes of a dynamic-shape model, refreshed by `set_input_shapes`.
+        // `None` until the first call, in which case `info` applies.
+        current: Option<RknnModelInfo>,
+        validate_inputs: bool,
+    }
+
+    /// Owns an `rknn_context` and destroys it on drop.
+    ///
+    /// Shared through `Arc` so that contexts created from another one (e.g. by
+    /// `rknn_dup_context`) keep their source alive until they are destroyed themselves.
+    #[derive(Debug)]
+    struct RknnContextGuard {
+        context: rknn_sys::rknn_context,
+        // Flags passed to `rknn_init`, used to check query preconditions.
+        flags: RknnInitFlags,
+        // Context whose weights this one uses. Released after `rknn_destroy(context)`
+        // because fields drop after `Drop::drop`.
+        _source: Option<Arc<RknnContextGuard>>,
+    }
+
+    // Queries shared by `Rknn` and `RknnModelInspector`.
+    impl RknnContextGuard {
+        /// `rknn_query` into a plain C struct.
+        fn query<T>(
+            &self,
+            query_cmd: rknn_sys::rknn_query_cmd,
+            info: &mut T,
+            api: &'static str,
+        ) -> Result<(), Error> {
+            let result = unsafe {
+                rknn_sys::rknn_query(
+                    self.context,
+                    query_cmd,
+                    info as *mut T as *mut c_void,
+                    mem::size_of::<T>() as u32,
+                )
+            };
+            if result != 0 {
+                return rkerr!(api, result);
+            }
+            Ok(())
+        }
+
+        fn sdk_version(&self) -> Result<RknnSdkVersion, Error> {
+            let mut version = rknn_sys::_rknn_sdk_version {
+                api_version: [0; 256],
+                drv_version: [0; 256],
+            };
+            let result = unsafe {
+                rknn_sys::rknn_query(
+                    self.context,
+                    rknn_sys::_rknn_query_cmd_RKNN_QUERY_SDK_VERSION,
+                    &mut version as *mut rknn_sys::_rknn_sdk_version as *mut c_void,
+                    mem::size_of::<rknn_sys::_rknn_sdk_version>() as u32,
+                )
+            };
+            if result != 0 {
+                return rkerr!("rknn_query(RKNN_QUERY_SDK_VERSION)", result);
+            }
+            Ok(RknnSdkVersion {
+                api_version: c_char_array_to_string(&version.api_version),
+                drv_version: c_char_array_to_string(&version.drv_version),
+            })
+        }
+
+        fn io_num(&self) -> Result<RknnInputOutputNum, Error> {
+            let mut io_num = rknn_sys::_rknn_input_output_num {
+                n_input: 0,
+                n_output: 0,
+            };
+            let result = unsafe {
+                rknn_sys::rknn_query(
+                    self.context,
+                    rknn_sys::_rknn_query_cmd_RKNN_QUERY_IN_OUT_NUM,
+                    &mut io_num as *mut rknn_sys::_rknn_input_output_num as *mut c_void,
+                    mem::size_of::<rknn_sys::_rknn_input_output_num>() as u32,
+                )
+            };
+            if result != 0 {
+                return rkerr!("rknn_query(RKNN_QUERY_IN_OUT_NUM)", result);
+            }
+            Ok(RknnInputOutputNum {
+                n_input: io_num.n_input,
+                n_output: io_num.n_output,
+            })
+        }
+
+        fn mem_size(&self) -> Result<RknnMemSize, Error> {
+            let mut mem_size: rknn_sys::_rknn_mem_size = unsafe { mem::zeroed() };
+            self.query(
+                rknn_sys::_rknn_query_cmd_RKNN_QUERY_MEM_SIZE,
+                &mut mem_size,
+                "rknn_query(RKNN_QUERY_MEM_SIZE)",
+            )?;
+            Ok(RknnMemSize {
+                total_weight_size: mem_size.total_weight_size,
+                total_internal_size: mem_size.total_internal_size,
+                total_dma_allocated_size: mem_size.total_dma_allocated_size,
+                total_sram_size: mem_size.total_sram_size,
+                free_sram_size: mem_size.free_sram_size,
+            })
+        }
+
+        fn custom_string(&self) -> Result<Option<String>, Error> {
+            let mut custom: rknn_sys::_rknn_custom_string = unsafe { mem::zeroed() };
+            self.query(
+                rknn_sys::_rknn_query_cmd_RKNN_QUERY_CUSTOM_STRING,
+                &mut custom,
+                "rknn_query(RKNN_QUERY_CUSTOM_STRING)",
+            )?;
+            let text = c_char_array_to_string(&custom.string);
+            Ok((!text.is_empty()).then_some(text))
+        }
+
+        #[cfg(feature = "serde")]
+        fn custom_metadata<T: serde::de::DeserializeOwned>(&self) -> Result<Option<T>, Error> {
+            match self.custom_string()? {
+                Some(text) => serde_json::from_str(&text)
+                    .map(Some)
+                    .map_err(|e| Error::InvalidMetadata(e.to_string())),
+                None => Ok(None),
+            }
+        }
+
+        fn input_ranges(&self) -> Result<Vec<RknnInputRange>, Error> {
+            let io_num = self.io_num()?;
+            let mut ranges = Vec::with_capacity(io_num.n_input as usize);
+            for index in 0..io_num.n_input {
+                // 512 x 16 dims: keep it off the stack.
+                let mut range: Box<rknn_sys::_rknn_input_range> =
+                    Box::new(unsafe { mem::zeroed() });
+                range.index = index;
+                self.query(
+                    rknn_sys::_rknn_query_cmd_RKNN_QUERY_INPUT_DYNAMIC_RANGE,
+                    &mut *range,
+                    "rknn_query(RKNN_QUERY_INPUT_DYNAMIC_RANGE)",
+                )?;
+                ranges.push(RknnInputRange::from(&*range));
+            }
+            Ok(ranges)
+        }
+
+        fn query_tensor_attr(
+            &self,
+            index: u32,
+            query_cmd: rknn_sys::rknn_query_cmd,
+        ) -> Result<RknnTensorAttr, Error> {
+            self.query_raw_tensor_attr(index, query_cmd).map(Into::into)
+        }
+
+        fn query_raw_tensor_attr(
+            &self,
+            index: u32,
+            query_cmd: rknn_sys::rknn_query_cmd,
+        ) -> Result<rknn_sys::_rknn_tensor_attr, Error> {
+            let mut attr: rknn_sys::_rknn_tensor_attr = unsafe { mem::zeroed() };
+            attr.index = index;
+            let result = unsafe {
+                rknn_sys::rknn_query(
+                    self.context,
+                    query_cmd,
+                    &mut attr as *mut rknn_sys::_rknn_tensor_attr as *mut c_void,
+                    mem::size_of::<rknn_sys::_rknn_tensor_attr>() as u32,
+                )
+            };
+            if result != 0 {
+                return rkerr!("rknn_query(tensor_attr)", result);
+            }
+            Ok(attr)
+        }
+
+        fn query_tensor_attrs(
+            &self,
+            query_cmd: rknn_sys::rknn_query_cmd,
+            count: u32,
+        ) -> Result<Vec<RknnTensorAttr>, Error> {
+            let mut attrs = Vec::with_capacity(count as usize);
+            for i in 0..count {
+                attrs.push(self.query_tensor_attr(i, query_cmd)?);
+            }
+            Ok(attrs)
+        }
+
+        fn input_attrs_with(
+            &self,
+            query_cmd: rknn_sys::rknn_query_cmd,
+        ) -> Result<Vec<RknnTensorAttr>, Error> {
+            let io_num = self.io_num()?;
+            self.query_tensor_attrs(query_cmd, io_num.n_input)
+        }
+
+        fn output_attrs_with(
+            &self,
+            query_cmd: rknn_sys::rknn_query_cmd,
+        ) -> Result<Vec<RknnTensorAttr>, Error> {
+            let io_num = self.io_num()?;
+            self.query_tensor_attrs(query_cmd, io_num.n_output)
+        }
+
+        fn input_attrs(&self) -> Result<Vec<RknnTensorAttr>, Error> {
+            self.input_attrs_with(rknn_sys::_rknn_query_cmd_RKNN_QUERY_INPUT_ATTR)
+        }
+
+        fn output_attrs(&self) -> Result<Vec<RknnTensorAttr>, Error> {
+            self.output_attrs_with(rknn_sys::_rknn_query_cmd_RKNN_QUERY_OUTPUT_ATTR)
+        }
+
+        fn model_info(&self) -> Result<RknnModelInfo, Error> {
+            let io_num = self.io_num()?;
+            let input_attrs = self.query_tensor_attrs(
+                rknn_sys::_rknn_query_cmd_RKNN_QUERY_INPUT_ATTR,
+                io_num.n_input,
+            )?;
+            let output_attrs = self.query_tensor_attrs(
+                rknn_sys::_rknn_query_cmd_RKNN_QUERY_OUTPUT_ATTR,
+                io_num.n_output,
+            )?;
+            Ok(RknnModelInfo {
+                io_num,
+                input_attrs,
+                output_attrs,
+            })
+        }
+    }
+
+    impl Drop for RknnContextGuard {
+        fn drop(&mut self) {
+            if self.context != 0 {
+                unsafe { rknn_sys::rknn_destroy(self.context) };
+            }
+        }
+    }
+
+    impl Rknn {
+        fn from_guard(guard: RknnContextGuard) -> Result<Self, Error> {
+            Ok(Rknn {
+                context: guard.context,
+                info: guard.model_info()?,
+                current: None,
+                validate_inputs: true,
+                guard: Arc::new(guard),
+            })
+        }
+
+        /// Initialize an RKNN model.
+        ///
+        /// # Parameters
+        ///
+        /// - `model_path`: The path to the model file.
+        ///
+        /// # Returns
+        ///
+        /// If successful, returns an `Rknn` instance; otherwise, returns an `Error`.
+        #[deprecated(since = "0.2.4", note = "use Rknn::new() instead")]
+        pub fn rknn_init<P: AsRef<std::path::Path>>(model_path: P) -> Result<Self, Error> {
+            RknnBuilder::new().build(model_path.as_ref())
+        }
+
+        #[allow(deprecated)]
+        pub fn new<P: AsRef<std::path::Path>>(model_path: P) -> Result<Self, Error> {
+            Self::rknn_init(model_path)
+        }
+
+        /// Initialize an RKNN model from an in-memory model blob.
+        ///
+        /// The runtime copies the model during `rknn_init`, so `model` only needs to
+        /// stay alive for the duration of this call. This works with `include_bytes!`
+        /// as well as with buffers fetched at runtime.
+        ///
+        /// # Parameters
+        ///
+        /// - `model`: The content of a `.rknn` file.
+        ///
+        /// # Returns
+        ///
+        /// If successful, returns an `Rknn` instance; otherwise, returns an `Error`.
+        pub fn from_bytes(model: &[u8]) -> Result<Self, Error> {
+            RknnBuilder::new().build(model)
+        }
+
+        /// Initialize an RKNN model from an owned in-memory model blob.
+        ///
+        /// Same as [`Rknn::from_bytes`]; the buffer is freed as soon as the runtime has
+        /// copied it, so the model does not stay resident twice.
+        pub fn from_vec(model: Vec<u8>) -> Result<Self, Error> {
+            RknnBuilder::new().build(model)
+        }
+
+        /// Call `rknn_init` and wrap the resulting context.
+        ///
+        /// # Safety
+        ///
+        /// `model` must point to a NUL-terminated path when `size` is 0, or to at least
+        /// `size` readable bytes otherwise. With `RKNN_FLAG_MODEL_BUFFER_ZERO_COPY` the
+        /// buffer must outlive the returned context, otherwise it is only used during the call.
+        unsafe fn init_raw(
+            model: *mut c_void,
+            size: u32,
+            flag: u32,
+            extend: *mut rknn_sys::rknn_init_extend,
+        ) -> Result<rknn_sys::rknn_context, Error> {
+            let mut context: rknn_sys::rknn_context = 0;
+            let result = rknn_sys::rknn_init(&mut context, model, size, flag, extend);
+            if result != 0 {
+                return rkerr!("rknn_init", result);
+            }
+            Ok(context)
+        }
+
+        /// Load `model_path` into a new context that shares weight memory with `source`.
+        ///
+        /// Uses `RKNN_FLAG_SHARE_WEIGHT_MEM` with `rknn_init_extend.ctx` set to `source`,
+        /// so the weights are only resident once no matter how many contexts use them.
+        /// `model_path` must be the model `source` was loaded from. `source` is kept
+        /// alive until the returned context is dropped.
+        ///
+        /// Use [`RknnBuilder::share_weights_with`] to combine this with other init flags.
+        pub fn new_sharing_weights<P: AsRef<Path>>(
+            source: &Rknn,
+            model_path: P,
+        ) -> Result<Self, Error> {
+            RknnBuilder::new()
+                .share_weights_with(source)
+                .build(model_path.as_ref())
+        }
+
+        /// Load a model stored at `offset..offset + size` inside a larger file.
+        ///
+        /// Useful when several `.rknn` models are packed into one bundle. The model is
+        /// loaded with `RKNN_FLAG_MODEL_BUFFER_ZERO_COPY` and `rknn_init_extend`'s
+        /// `real_model_offset` / `real_model_size`, so it is not copied out of the file
+        /// first. The region is checked to lie inside the file before the runtime is called.
+        ///
+        /// # Parameters
+        ///
+        /// - `file`: A path, `&File` or borrowed file descriptor, see [`RknnModelFile`].
+        /// - `offset`: Byte offset of the model inside the file.
+        /// - `size`: Byte size of the model.
+        ///
+        /// # Returns
+        ///
+        /// If successful, returns an `Rknn` instance; otherwise, returns an `Error`.
+        pub fn from_file_region<F: Into<RknnModelFile>>(
+            file: F,
+            offset: u64,
+            size: u64,
+        ) -> Result<Self, Error> {
+            RknnBuilder::new().build(RknnModelSource::FileRegion {
+                file: file.into(),
+                offset,
+                size,
+            })
+        }
+
+        /// Create a new context that shares the loaded model with this one.
+        ///
+        /// Wraps `rknn_dup_context`. The model weights are not loaded again, which makes
+        /// this a cheap way to give every worker thread its own context.
+        ///
+        /// The duplicate is an independent `Rknn`: it has its own inputs and outputs and
+        /// can run concurrently with `self`. Because it uses the weights of `self`, it
+        /// keeps the original context alive; dropping `self` first only destroys the
+        /// original context once the last duplicate has been dropped. The input validation
+        /// setting of `self` is carried over.
+        ///
+        /// # Returns
+        ///
+        /// If successful, returns the new `Rknn` instance; otherwise, returns an `Error`.
+        pub fn try_duplicate(&self) -> Result<Rknn, Error> {
+            let mut context_in = self.context;
+            let mut context_out: rknn_sys::rknn_context = 0;
+            let result = unsafe { rknn_sys::rknn_dup_context(&mut context_in, &mut context_out) };
+            if result != 0 {
+                return rkerr!("rknn_dup_context", result);
+            }
+            let mut rknn = Rknn::from_guard(RknnContextGuard {
+                context: context_out,
+                flags: self.guard.flags,
+                _source: Some(Arc::clone(&self.guard)),
+            })?;
+            rknn.validate_inputs = self.validate_inputs;
+            Ok(rknn)
+        }
+
+        /// Set the model's input.
+        ///
+        /// # Parameters
+        ///
+        /// - `input`: A reference to the generic input structure `RknnInput<T>`.
+        ///
+        /// # Returns
+        ///
+        /// If successful, returns `Ok(()`; otherwise, returns an `Error`. `input.type_`
+        /// must be the tensor type of `T`.
+        pub fn input_set<T: TensorElement>(&self, input: &RknnInput<T>) -> Result<(), Error> {
+            if input.type_ != T::TYPE {
+                return Err(Error::TypeMismatch {
+                    what: format!("input {}: type_ of RknnInput", input.index),
+                    expected: T::TYPE,
+                    actual: input.type_,
+                });
+            }
+            self.input_set_slice(input.index, &input.buf, input.pass_through, input.fmt)
+        }
+
+        /// Set the model's input from a borrowed slice.
+        ///
+        /// The tensor type passed to the runtime is inferred from `T`, e.g. `Float32` for
+        /// `&[f32]` and `Uint8` for `&[u8]`.
+        ///
+        /// # Parameters
+        ///
+        /// - `index`: The input index.
+        /// - `buf`: The input data.
+        /// - `pass_through`: Pass the buffer to the NPU without conversion.
+        /// - `fmt`: The layout of `buf`.
+        ///
+        /// # Returns
+        ///
+        /// If successful, returns `Ok(()`; otherwise, returns an `Error`.
+        pub fn input_set_slice<T: TensorElement>(
+            &self,
+            index: usize,
+            buf: &[T],
+            pass_through: bool,
+            fmt: RknnTensorFormat,
+        ) -> Result<(), Error> {
+            let type_ = T::TYPE;
+            self.check_input(index, mem::size_of_val(buf), pass_through, type_)?;
+            let total_bytes = mem::size_of_val(buf) as u32;
+            let mut c_input = rknn_sys::rknn_input {
+                index: index as u32,
+                buf: buf.as_ptr() as *mut c_void,
+                size: total_bytes,
+                pass_through: if pass_through { 1 } else { 0 },
+                type_: type_ as u32,
+                fmt: fmt as u32,
+            };
+
+            let result = unsafe { rknn_sys::rknn_inputs_set(self.context, 1, &mut c_input) };
+            if result != 0 {
+                return rkerr!("rknn_inputs_set", result);
+            }
+            Ok(())
+        }
+
+        /// Set the model's input from an `ndarray` view.
+        ///
+        /// The shape of `array` is checked against the input's `dims`. If `fmt` is NCHW and
+        /// the model declares NHWC (or the other way round), the dims are permuted first.
+        /// Views that are not contiguous in row-major order are copied.
+        ///
+        /// # Parameters
+        ///
+        /// - `index`: The input index.
+        /// - `array`: The input data, in the layout given by `fmt`.
+        /// - `pass_through`: Pass the buffer to the NPU without conversion.
+        /// - `fmt`: The layout of `array`.
+        ///
+        /// # Returns
+        ///
+        /// If successful, returns `Ok(()`; otherwise, returns an `Error`.
+        #[cfg(feature = "ndarray")]
+        pub fn input_set_array<T: TensorElement, D: Dimension>(
+            &self,
+            index: usize,
+            array: ArrayView<'_, T, D>,
+            pass_through: bool,
+            fmt: RknnTensorFormat,
+        ) -> Result<(), Error> {
+            let attrs = self.current_input_attrs();
+            let attr = attrs.get(index).ok_or(Error::IndexOutOfRange {
+                kind: "input",
+                index,
+                count: attrs.len(),
+            })?;
+            let dims: Vec<usize> = attr.dims.iter().map(|&d| d as usize).collect();
+            let expected = match (attr.fmt, fmt, dims.as_slice()) {
+                (RknnTensorFormat::NHWC, RknnTensorFormat::NCHW, &[n, h, w, c]) => {
+                    vec![n, c, h, w]
+                }
+                (RknnTensorFormat::NCHW, RknnTensorFormat::NHWC, &[n, c, h, w]) => {
+                    vec![n, h, w, c]
+                }
+                _ => dims,
+            };
+            if array.shape() != expected.as_slice() {
+                return Err(Error::ShapeMismatch {
+                    name: attr.name.clone(),
+                    expected,
+                    actual: array.shape().to_vec(),
+                });
+            }
+            match array.as_slice() {
+                Some(buf) => self.input_set_slice(index, buf, pass_through, fmt),
+                None => {
+                    let buf: Vec<T> = array.iter().copied().collect();
+                    self.input_set_slice(index, &buf, pass_through, fmt)
+                }
+            }
+        }
+
+        /// Set an input by tensor name instead of index.
+        ///
+        /// Same as [`Rknn::input_set_slice`], with the index resolved through
+        /// [`Rknn::input_index`].
+        pub fn input_set_by_name<T: TensorElement>(
+            &self,
+            name: &str,
+            buf: &[T],
+            pass_through: bool,
+            fmt: RknnTensorFormat,
+        ) -> Result<(), Error> {
+            let index = self.input_index(name)?;
+            self.input_set_slice(index, buf, pass_through, fmt)
+        }
+
+        /// Enable or disable the input size check done before every `rknn_inputs_set`.
+        ///
+        /// When enabled (the default), a buffer must hold exactly `n_elems` elements of the
+        /// declared `type_` for the current input shape. With `pass_through`, it must match
+        /// the model's own `size` or `size_with_stride` instead. Disable it on hot paths
+        /// whose buffers are known to be correct.
+        pub fn set_validate_inputs(&mut self, enabled: bool) {
+            self.validate_inputs = enabled;
+        }
+
+        fn check_input(
+            &self,
+            index: usize,
+            bytes: usize,
+            pass_through: bool,
+            type_: RknnTensorType,
+        ) -> Result<(), Error> {
+            if !self.validate_inputs {
+                return Ok(());
+            }
+            let attrs = self.current_input_attrs();
+            let attr = attrs.get(index).ok_or(Error::IndexOutOfRange {
+                kind: "input",
+                index,
+                count: attrs.len(),
+            })?;
+            // With pass_through the buffer is already in the model's type and layout.
+            let elem_type = if pass_through { attr.type_ } else { type_ };
+            let Some(bits) = elem_type.size_in_bits() else {
+                return Ok(());
+            };
+            let expected_bytes = (attr.n_elems as usize * bits).div_ceil(8);
+            let matches = if pass_through {
+                bytes == attr.size as usize
+                    || bytes == attr.size_with_stride as usize
+                    || bytes == expected_bytes
+            } else {
+                bytes == expected_bytes
+            };
+            if matches {
+                return Ok(());
+            }
+            Err(Error::InputSizeMismatch {
+                name: attr.name.clone(),
+                dims: attr.dims.clone(),
+                expected: attr.n_elems as usize,
+                actual: bytes * 8 / bits,
+            })
+        }
+
+        /// Quantize `data` for input `index`, to be set with `pass_through`.
+        ///
+        /// Uses the input's `qnt_type`, `zp`, `scale` and `fl`, rounding half to even and
+        /// saturating like the RKNN toolkit. `data` must hold `n_elems` values in the
+        /// input's own layout (`fmt`), and `T` must match the input's `type_`.
+        ///
+        /// # Examples
+        ///
+        /// ```no_run
+        /// use rknn_rs::prelude::*;
+        ///
+        /// fn main() -> Result<(), Error> {
+        ///     let rknn = Rknn::new("model_int8.rknn")?;
+        ///     let pixels = vec![0.5f32; rknn.input_attrs()[0].n_elems as usize];
+        ///     let quantized = rknn.quantize_input::<i8>(0, &pixels)?;
+        ///     rknn.input_set_slice(0, &quantized, true, rknn.input_attrs()[0].fmt)?;
+        ///     Ok(())
+        /// }
+        /// ```
+        pub fn quantize_input<T: QuantizedElement>(
+            &self,
+            index: usize,
+            data: &[f32],
+        ) -> Result<Vec<T>, Error> {
+            let mut dst = vec![T::zeroed(); data.len()];
+            self.quantize_input_to(index, data, &mut dst)?;
+            Ok(dst)
+        }
+
+        /// Same as [`Rknn::quantize_input`], writing into the start of `mem`. Call
+        /// [`RknnTensorMemory::sync`] afterwards if the memory is cached.
+        ///
+        /// The values are written densely in the input's `fmt`; `w_stride` and
+        /// `size_with_stride` are not applied, so `mem` must not expect a padded layout.
+        pub fn quantize_input_into<T: QuantizedElement>(
+            &self,
+            index: usize,
+            data: &[f32],
+            mem: &mut RknnTensorMemory<'_>,
+        ) -> Result<(), Error> {
+            let dst = mem.as_mut_slice::<T>()?;
+            if data.len() > dst.len() {
+                return Err(Error::SizeMismatch {
+                    what: "Input data is too large for tensor memory (elements)".to_string(),
+                    expected: dst.len(),
+                    actual: data.len(),
+                });
+            }
+            self.quantize_input_to(index, data, &mut dst[..data.len()])
+        }
+
+        fn quantize_input_to<T: QuantizedElement>(
+            &self,
+            index: usize,
+            data: &[f32],
+            dst: &mut [T],
+        ) -> Result<(), Error> {
+            let attrs = self.current_input_attrs();
+            let attr = attrs.get(index).ok_or(Error::IndexOutOfRange {
+                kind: "input",
+                index,
+                count: attrs.len(),
+            })?;
+            if T::TYPE != attr.type_ {
+                return Err(Error::TypeMismatch {
+                    what: format!("input '{}'", attr.name),
+                    expected: attr.type_,
+                    actual: T::TYPE,
+                });
+            }
+            if data.len() != attr.n_elems as usize {
+                return Err(Error::InputSizeMismatch {
+                    name: attr.name.clone(),
+                    dims: attr.dims.clone(),
+                    expected: attr.n_elems as usize,
+                    actual: data.len(),
+                });
+            }
+            let params = RknnQuantParams::from_attr(attr);
+            for (d, &x) in dst.iter_mut().zip(data) {
+                *d = params.quantize_to(x);
+            }
+            Ok(())
+        }
+
+        /// Set multiple inputs in a single `rknn_inputs_set` call.
+        ///
+        /// The RKNN 2.3.x runtime may behave incorrectly when inputs are set one-by-one
+        /// (n=1 each call) for models with multiple inputs. Use this method to set all
+        /// inputs atomically in one call when that is the case.
+        ///
+        /// Each element of `inputs` is `(index, raw_bytes, pass_through, type_, fmt)`.
+        /// Use `bytemuck::cast_slice` or `bytemuck::bytes_of` to obtain `&[u8]` from typed slices.
+        pub fn inputs_set_batch(
+            &self,
+            inputs: &[(usize, &[u8], bool, RknnTensorType, RknnTensorFormat)],
+        ) -> Result<(), Error> {
+            for (idx, data, pass_through, type_, _) in inputs {
+                self.check_input(*idx, data.len(), *pass_through, *type_)?;
+            }
             let mut c_inputs: Vec<rknn_sys::rknn_input> = inputs
                 .iter()
                 .map(|(idx, data, pass_through, type_, fmt)| rknn_sys::rknn_input {
@@ -592,156 +2480,509 @@ pub mod prelude {
                 )
             };
             if result != 0 {
-                return rkerr!("rknn_inputs_set failed.", result);
+                return rkerr!("rknn_inputs_set", result);
             }
             Ok(())
         }
 
-        /// Run the RKNN model.
-        ///
-        /// # Returns
+        /// Run the RKNN model.
+        ///
+        /// # Returns
+        ///
+        /// If successful, returns `Ok(()`; otherwise, returns an `Error`.
+        pub fn run(&self) -> Result<(), Error> {
+            let result = unsafe { rknn_sys::rknn_run(self.context, null_mut()) };
+            if result != 0 {
+                return rkerr!("rknn_run", result);
+            }
+            Ok(())
+        }
+
+        /// Duration of the last inference (`RKNN_QUERY_PERF_RUN`).
+        ///
+        /// Only valid after the outputs of the run have been fetched.
+        pub fn perf_run(&self) -> Result<Duration, Error> {
+            let mut perf_run = rknn_sys::_rknn_perf_run { run_duration: 0 };
+            self.guard.query(
+                rknn_sys::_rknn_query_cmd_RKNN_QUERY_PERF_RUN,
+                &mut perf_run,
+                "rknn_query(RKNN_QUERY_PERF_RUN)",
+            )?;
+            Ok(Duration::from_micros(perf_run.run_duration.max(0) as u64))
+        }
+
+        /// Per-layer profile of the last inference (`RKNN_QUERY_PERF_DETAIL`).
+        ///
+        /// The context must be created with `RknnInitFlags::COLLECT_PERF_MASK` (see
+        /// [`RknnBuilder`]), and the query is only valid after the outputs of the run
+        /// have been fetched.
+        ///
+        /// # Examples
+        ///
+        /// ```no_run
+        /// use rknn_rs::prelude::*;
+        ///
+        /// fn main() -> Result<(), Error> {
+        ///     let rknn = RknnBuilder::new()
+        ///         .flags(RknnInitFlags::COLLECT_PERF_MASK)
+        ///         .build("model.rknn")?;
+        ///     rknn.run()?;
+        ///     drop(rknn.outputs_get::<f32>()?);
+        ///     for layer in rknn.perf_detail()?.layers {
+        ///         println!("{} {} {}us", layer.id, layer.op_type, layer.time_us);
+        ///     }
+        ///     Ok(())
+        /// }
+        /// ```
+        pub fn perf_detail(&self) -> Result<RknnPerfDetail, Error> {
+            if !self.guard.flags.contains(RknnInitFlags::COLLECT_PERF_MASK) {
+                return Err(Error::InvalidConfig(
+                    "perf_detail requires a context created with RknnInitFlags::COLLECT_PERF_MASK."
+                        .to_string(),
+                ));
+            }
+            let mut detail = rknn_sys::_rknn_perf_detail {
+                perf_data: null_mut(),
+                data_len: 0,
+            };
+            self.guard.query(
+                rknn_sys::_rknn_query_cmd_RKNN_QUERY_PERF_DETAIL,
+                &mut detail,
+                "rknn_query(RKNN_QUERY_PERF_DETAIL)",
+            )?;
+            if detail.perf_data.is_null() {
+                return Err(Error::NullBuffer(
+                    "rknn_query(RKNN_QUERY_PERF_DETAIL) returned no data.".to_string(),
+                ));
+            }
+            // The buffer is owned by the runtime; copy it out right away.
+            let bytes = unsafe {
+                slice::from_raw_parts(detail.perf_data as *const u8, detail.data_len as usize)
+            };
+            let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+            let text = String::from_utf8_lossy(&bytes[..end]);
+            Ok(RknnPerfDetail::parse(&text))
+        }
+
+        pub fn set_batch_core_num(&self, core_num: i32) -> Result<(), Error> {
+            let result = unsafe { rknn_sys::rknn_set_batch_core_num(self.context, core_num) };
+            if result != 0 {
+                return rkerr!("rknn_set_batch_core_num", result);
+            }
+            Ok(())
+        }
+
+        pub fn set_core_mask(&self, core_mask: RknnCoreMask) -> Result<(), Error> {
+            let result = unsafe {
+                rknn_sys::rknn_set_core_mask(self.context, core_mask as rknn_sys::rknn_core_mask)
+            };
+            if result != 0 {
+                return rkerr!("rknn_set_core_mask", result);
+            }
+            Ok(())
+        }
+
+        pub fn sdk_version(&self) -> Result<RknnSdkVersion, Error> {
+            self.guard.sdk_version()
+        }
+
+        /// Number of inputs and outputs, cached at construction.
+        pub fn io_num(&self) -> RknnInputOutputNum {
+            self.info.io_num
+        }
+
+        /// Input attributes as declared by the model, cached at construction.
+        pub fn input_attrs(&self) -> &[RknnTensorAttr] {
+            &self.info.input_attrs
+        }
+
+        /// Output attributes as declared by the model, cached at construction.
+        pub fn output_attrs(&self) -> &[RknnTensorAttr] {
+            &self.info.output_attrs
+        }
+
+        /// Input/output counts and attributes, cached at construction.
+        pub fn model_info(&self) -> &RknnModelInfo {
+            &self.info
+        }
+
+        /// Index of the input tensor called `name`.
+        ///
+        /// Returns [`Error::UnknownTensorName`] listing the valid names if there is none.
+        pub fn input_index(&self, name: &str) -> Result<usize, Error> {
+            tensor_index("input", &self.info.input_attrs, name)
+        }
+
+        /// Index of the output tensor called `name`.
+        ///
+        /// Returns [`Error::UnknownTensorName`] listing the valid names if there is none.
+        pub fn output_index(&self, name: &str) -> Result<usize, Error> {
+            tensor_index("output", &self.info.output_attrs, name)
+        }
+
+        /// Input attributes in the NPU's native layout (`RKNN_QUERY_NATIVE_INPUT_ATTR`).
+        ///
+        /// Use these, including `w_stride`/`h_stride` and `size_with_stride`, to size
+        /// zero-copy buffers that are passed to the NPU without conversion.
+        pub fn native_input_attrs(&self) -> Result<Vec<RknnTensorAttr>, Error> {
+            self.guard
+                .input_attrs_with(rknn_sys::_rknn_query_cmd_RKNN_QUERY_NATIVE_INPUT_ATTR)
+        }
+
+        /// Output attributes in the NPU's native layout, usually NC1HWC2
+        /// (`RKNN_QUERY_NATIVE_OUTPUT_ATTR`).
+        pub fn native_output_attrs(&self) -> Result<Vec<RknnTensorAttr>, Error> {
+            self.guard
+                .output_attrs_with(rknn_sys::_rknn_query_cmd_RKNN_QUERY_NATIVE_OUTPUT_ATTR)
+        }
+
+        /// Native input attributes in NHWC layout (`RKNN_QUERY_NATIVE_NHWC_INPUT_ATTR`).
+        pub fn native_nhwc_input_attrs(&self) -> Result<Vec<RknnTensorAttr>, Error> {
+            self.guard
+                .input_attrs_with(rknn_sys::_rknn_query_cmd_RKNN_QUERY_NATIVE_NHWC_INPUT_ATTR)
+        }
+
+        /// Native output attributes in NHWC layout (`RKNN_QUERY_NATIVE_NHWC_OUTPUT_ATTR`).
+        pub fn native_nhwc_output_attrs(&self) -> Result<Vec<RknnTensorAttr>, Error> {
+            self.guard
+                .output_attrs_with(rknn_sys::_rknn_query_cmd_RKNN_QUERY_NATIVE_NHWC_OUTPUT_ATTR)
+        }
+
+        /// Native input attributes for the currently active shape of a dynamic-shape model
+        /// (`RKNN_QUERY_CURRENT_NATIVE_INPUT_ATTR`).
+        pub fn current_native_input_attrs(&self) -> Result<Vec<RknnTensorAttr>, Error> {
+            self.guard
+                .input_attrs_with(rknn_sys::_rknn_query_cmd_RKNN_QUERY_CURRENT_NATIVE_INPUT_ATTR)
+        }
+
+        /// Native output attributes for the currently active shape of a dynamic-shape model
+        /// (`RKNN_QUERY_CURRENT_NATIVE_OUTPUT_ATTR`).
+        pub fn current_native_output_attrs(&self) -> Result<Vec<RknnTensorAttr>, Error> {
+            self.guard
+                .output_attrs_with(rknn_sys::_rknn_query_cmd_RKNN_QUERY_CURRENT_NATIVE_OUTPUT_ATTR)
+        }
+
+        /// Input attributes for the currently active shape of a dynamic-shape model
+        /// (`RKNN_QUERY_CURRENT_INPUT_ATTR`).
         ///
-        /// If successful, returns `Ok(()`; otherwise, returns an `Error`.
-        pub fn run(&self) -> Result<(), Error> {
-            let result = unsafe { rknn_sys::rknn_run(self.context, null_mut()) };
-            if result != 0 {
-                return rkerr!("rknn_run failed.", result);
-            }
-            Ok(())
+        /// Cached and refreshed by [`Rknn::set_input_shapes`]; until then, this is the
+        /// same as [`Rknn::input_attrs`].
+        pub fn current_input_attrs(&self) -> &[RknnTensorAttr] {
+            &self.current.as_ref().unwrap_or(&self.info).input_attrs
         }
 
-        pub fn set_batch_core_num(&self, core_num: i32) -> Result<(), Error> {
-            let result = unsafe { rknn_sys::rknn_set_batch_core_num(self.context, core_num) };
-            if result != 0 {
-                return rkerr!("rknn_set_batch_core_num failed.", result);
-            }
-            Ok(())
+        /// Output attributes for the currently active shape of a dynamic-shape model
+        /// (`RKNN_QUERY_CURRENT_OUTPUT_ATTR`).
+        ///
+        /// Cached and refreshed by [`Rknn::set_input_shapes`]; until then, this is the
+        /// same as [`Rknn::output_attrs`].
+        pub fn current_output_attrs(&self) -> &[RknnTensorAttr] {
+            &self.current.as_ref().unwrap_or(&self.info).output_attrs
         }
 
-        pub fn set_core_mask(&self, core_mask: RknnCoreMask) -> Result<(), Error> {
-            let result = unsafe {
-                rknn_sys::rknn_set_core_mask(self.context, core_mask as rknn_sys::rknn_core_mask)
-            };
-            if result != 0 {
-                return rkerr!("rknn_set_core_mask failed.", result);
-            }
-            Ok(())
+        /// The shapes each input of a dynamic-shape model accepts.
+        ///
+        /// Static models report no shapes.
+        pub fn input_dynamic_ranges(&self) -> Result<Vec<RknnInputRange>, Error> {
+            self.guard.input_ranges()
         }
 
-        pub fn sdk_version(&self) -> Result<RknnSdkVersion, Error> {
-            let mut version = rknn_sys::_rknn_sdk_version {
-                api_version: [0; 256],
-                drv_version: [0; 256],
-            };
-            let result = unsafe {
-                rknn_sys::rknn_query(
-                    self.context,
-                    rknn_sys::_rknn_query_cmd_RKNN_QUERY_SDK_VERSION,
-                    &mut version as *mut rknn_sys::_rknn_sdk_version as *mut c_void,
-                    mem::size_of::<rknn_sys::_rknn_sdk_version>() as u32,
-                )
-            };
-            if result != 0 {
-                return rkerr!("rknn_query sdk_version failed.", result);
-            }
-            Ok(RknnSdkVersion {
-                api_version: c_char_array_to_string(&version.api_version),
-                drv_version: c_char_array_to_string(&version.drv_version),
-            })
+        /// Switch a dynamic-shape model to another set of input shapes.
+        ///
+        /// The shapes are given in the layout reported by [`Rknn::input_dynamic_ranges`]
+        /// and must together match one of the shape sets the model was compiled with.
+        /// After this call, [`Rknn::current_input_attrs`] and
+        /// [`Rknn::current_output_attrs`] describe the new shapes.
+        ///
+        /// # Parameters
+        ///
+        /// - `shapes`: One shape per input, in input order.
+        ///
+        /// # Returns
+        ///
+        /// If successful, returns `Ok(())`; otherwise, returns an `Error`.
+        pub fn set_input_shapes(&mut self, shapes: &[&[u32]]) -> Result<(), Error> {
+            let ranges = self.guard.input_ranges()?;
+            self.set_input_shapes_with(&ranges, shapes)
         }
 
-        pub fn io_num(&self) -> Result<RknnInputOutputNum, Error> {
-            let mut io_num = rknn_sys::_rknn_input_output_num {
-                n_input: 0,
-                n_output: 0,
-            };
-            let result = unsafe {
-                rknn_sys::rknn_query(
-                    self.context,
-                    rknn_sys::_rknn_query_cmd_RKNN_QUERY_IN_OUT_NUM,
-                    &mut io_num as *mut rknn_sys::_rknn_input_output_num as *mut c_void,
-                    mem::size_of::<rknn_sys::_rknn_input_output_num>() as u32,
-                )
-            };
-            if result != 0 {
-                return rkerr!("rknn_query in_out_num failed.", result);
+        fn set_input_shapes_with(
+            &mut self,
+            ranges: &[RknnInputRange],
+            shapes: &[&[u32]],
+        ) -> Result<(), Error> {
+            if shapes.len() != ranges.len() {
+                return Err(Error::SizeMismatch {
+                    what: "number of input shapes".to_string(),
+                    expected: ranges.len(),
+                    actual: shapes.len(),
+                });
+            }
+            let shape_sets = ranges.iter().map(|r| r.shapes.len()).min().unwrap_or(0);
+            if shape_sets == 0 {
+                return Err(Error::InvalidConfig(
+                    "Model has no dynamic input shapes".to_string(),
+                ));
+            }
+            let supported = (0..shape_sets).any(|k| {
+                ranges
+                    .iter()
+                    .zip(shapes)
+                    .all(|(range, shape)| range.shapes[k].as_slice() == *shape)
+            });
+            if !supported {
+                let sets: Vec<String> = (0..shape_sets)
+                    .map(|k| {
+                        let set: Vec<_> = ranges.iter().map(|r| &r.shapes[k]).collect();
+                        format!("{:?}", set)
+                    })
+                    .collect();
+                return Err(Error::InvalidArgument(format!(
+                    "Input shapes {:?} do not match any shape set of the model: {}",
+                    shapes,
+                    sets.join(", ")
+                )));
             }
-            Ok(RknnInputOutputNum {
-                n_input: io_num.n_input,
-                n_output: io_num.n_output,
-            })
-        }
 
-        fn query_tensor_attr(
-            &self,
-            index: u32,
-            query_cmd: rknn_sys::rknn_query_cmd,
-        ) -> Result<RknnTensorAttr, Error> {
-            let mut attr: rknn_sys::_rknn_tensor_attr = unsafe { mem::zeroed() };
-            attr.index = index;
+            let mut attrs = Vec::with_capacity(ranges.len());
+            for (range, shape) in ranges.iter().zip(shapes) {
+                let mut attr = self.guard.query_raw_tensor_attr(
+                    range.index,
+                    rknn_sys::_rknn_query_cmd_RKNN_QUERY_INPUT_ATTR,
+                )?;
+                attr.fmt = range.fmt as u32;
+                attr.n_dims = shape.len() as u32;
+                attr.dims = [0; rknn_sys::RKNN_MAX_DIMS as usize];
+                attr.dims[..shape.len()].copy_from_slice(shape);
+                attrs.push(attr);
+            }
             let result = unsafe {
-                rknn_sys::rknn_query(
+                rknn_sys::rknn_set_input_shapes(
                     self.context,
-                    query_cmd,
-                    &mut attr as *mut rknn_sys::_rknn_tensor_attr as *mut c_void,
-                    mem::size_of::<rknn_sys::_rknn_tensor_attr>() as u32,
+                    attrs.len() as u32,
+                    attrs.as_mut_ptr(),
                 )
             };
             if result != 0 {
-                return rkerr!("rknn_query tensor_attr failed.", result);
+                return rkerr!("rknn_set_input_shapes", result);
             }
-            Ok(attr.into())
+
+            // The cached shapes are stale from here on, even if the refresh below fails.
+            self.current = None;
+            self.current = Some(RknnModelInfo {
+                io_num: self.info.io_num,
+                input_attrs: self
+                    .guard
+                    .input_attrs_with(rknn_sys::_rknn_query_cmd_RKNN_QUERY_CURRENT_INPUT_ATTR)?,
+                output_attrs: self
+                    .guard
+                    .output_attrs_with(rknn_sys::_rknn_query_cmd_RKNN_QUERY_CURRENT_OUTPUT_ATTR)?,
+            });
+            Ok(())
         }
 
-        fn query_tensor_attrs(
-            &self,
-            query_cmd: rknn_sys::rknn_query_cmd,
-            count: u32,
-        ) -> Result<Vec<RknnTensorAttr>, Error> {
-            let mut attrs = Vec::with_capacity(count as usize);
-            for i in 0..count {
-                attrs.push(self.query_tensor_attr(i, query_cmd)?);
+        /// Run a dynamic-shape model on inputs of arbitrary size.
+        ///
+        /// Picks the smallest shape set that every input fits into, pads each input with
+        /// `pad_value` up to that shape, calls [`Rknn::set_input_shapes`], runs the model and
+        /// fetches all outputs as float32. An output axis is cropped back to the valid size
+        /// when the same axis of an input was padded and the output dimension equals the
+        /// padded size, e.g. the time axis of a sequence model. Other outputs are returned
+        /// at their full size; use `input_shapes` to interpret them.
+        ///
+        /// # Parameters
+        ///
+        /// - `inputs`: One `(data, dims)` pair per input, in input order and in the layout
+        ///   reported by [`Rknn::input_dynamic_ranges`].
+        /// - `pad_value`: The value written into the padded region.
+        ///
+        /// # Returns
+        ///
+        /// If successful, returns the selected shape set and the cropped outputs; otherwise,
+        /// returns an `Error`.
+        ///
+        /// # Examples
+        ///
+        /// ```no_run
+        /// use rknn_rs::prelude::*;
+        ///
+        /// fn main() -> Result<(), Error> {
+        ///     let mut rknn = Rknn::new("sensevoice.rknn")?;
+        ///     let frames = 173;
+        ///     let features = vec![0.0f32; frames * 560];
+        ///     let result = rknn.run_bucketed(&[(&features, &[1, frames as u32, 560])], 0.0)?;
+        ///     println!("logits: {:?}", result.outputs[0].dims);
+        ///     Ok(())
+        /// }
+        /// ```
+        pub fn run_bucketed(
+            &mut self,
+            inputs: &[(&[f32], &[u32])],
+            pad_value: f32,
+        ) -> Result<RknnBucketedRun, Error> {
+            let ranges = self.guard.input_ranges()?;
+            if inputs.len() != ranges.len() {
+                return Err(Error::SizeMismatch {
+                    what: "number of inputs".to_string(),
+                    expected: ranges.len(),
+                    actual: inputs.len(),
+                });
             }
-            Ok(attrs)
+            for (range, (data, dims)) in ranges.iter().zip(inputs) {
+                if dims.len() != range.n_dims as usize {
+                    return Err(Error::SizeMismatch {
+                        what: format!("number of dims of input '{}'", range.name),
+                        expected: range.n_dims as usize,
+                        actual: dims.len(),
+                    });
+                }
+                let n_elems: usize = dims.iter().map(|&d| d as usize).product();
+                if data.len() != n_elems {
+                    return Err(Error::SizeMismatch {
+                        what: format!("elements of input '{}' with dims {:?}", range.name, dims),
+                        expected: n_elems,
+                        actual: data.len(),
+                    });
+                }
+            }
+
+            let shape_sets = ranges.iter().map(|r| r.shapes.len()).min().unwrap_or(0);
+            let shape_set = (0..shape_sets)
+                .filter(|&k| {
+                    ranges.iter().zip(inputs).all(|(range, (_, dims))| {
+                        dims.iter().zip(&range.shapes[k]).all(|(d, max)| d <= max)
+                    })
+                })
+                .min_by_key(|&k| {
+                    ranges
+                        .iter()
+                        .map(|r| r.shapes[k].iter().map(|&d| d as u64).product::<u64>())
+                        .sum::<u64>()
+                })
+                .ok_or_else(|| {
+                    let dims: Vec<_> = inputs.iter().map(|(_, dims)| dims).collect();
+                    Error::InvalidArgument(format!(
+                        "Input shapes {:?} do not fit into any shape set of the model",
+                        dims
+                    ))
+                })?;
+
+            let input_shapes: Vec<Vec<u32>> =
+                ranges.iter().map(|r| r.shapes[shape_set].clone()).collect();
+            let padded: Vec<Vec<f32>> = inputs
+                .iter()
+                .zip(&input_shapes)
+                .map(|((data, dims), shape)| {
+                    let dims: Vec<usize> = dims.iter().map(|&d| d as usize).collect();
+                    let shape: Vec<usize> = shape.iter().map(|&d| d as usize).collect();
+                    let mut buf = vec![pad_value; shape.iter().product()];
+                    copy_region(data, &dims, &mut buf, &shape, &dims);
+                    buf
+                })
+                .collect();
+
+            let shapes: Vec<&[u32]> = input_shapes.iter().map(Vec::as_slice).collect();
+            self.set_input_shapes_with(&ranges, &shapes)?;
+            let batch: Vec<_> = ranges
+                .iter()
+                .zip(&padded)
+                .map(|(range, buf)| {
+                    (
+                        range.index as usize,
+                        bytemuck::cast_slice::<f32, u8>(buf),
+                        false,
+                        RknnTensorType::Float32,
+                        range.fmt,
+                    )
+                })
+                .collect();
+            self.inputs_set_batch(&batch)?;
+            self.run()?;
+
+            // (axis, padded size, valid size) of every padded input axis.
+            let padded_axes: Vec<(usize, u32, u32)> = inputs
+                .iter()
+                .zip(&input_shapes)
+                .flat_map(|((_, dims), shape)| {
+                    dims.iter()
+                        .zip(shape)
+                        .enumerate()
+                        .filter(|(_, (valid, padded))| valid < padded)
+                        .map(|(axis, (&valid, &padded))| (axis, padded, valid))
+                })
+                .collect();
+            let fetched = self.outputs_get_all(true)?;
+            let data = (0..fetched.len())
+                .map(|i| fetched.get::<f32>(i).map(|output| output.to_vec()))
+                .collect::<Result<Vec<_>, Error>>()?;
+            drop(fetched);
+            let outputs = data
+                .into_iter()
+                .zip(self.current_output_attrs())
+                .map(|(data, attr)| {
+                    let dims: Vec<usize> = attr.dims.iter().map(|&d| d as usize).collect();
+                    if data.len() != dims.iter().product::<usize>() {
+                        return RknnBucketedOutput {
+                            dims: attr.dims.clone(),
+                            data,
+                        };
+                    }
+                    let mut valid = dims.clone();
+                    for &(axis, padded, size) in &padded_axes {
+                        if dims.get(axis) == Some(&(padded as usize)) {
+                            valid[axis] = size as usize;
+                        }
+                    }
+                    if valid == dims {
+                        return RknnBucketedOutput {
+                            dims: attr.dims.clone(),
+                            data,
+                        };
+                    }
+                    let mut cropped = vec![0.0; valid.iter().product()];
+                    copy_region(&data, &dims, &mut cropped, &valid, &valid);
+                    RknnBucketedOutput {
+                        dims: valid.iter().map(|&d| d as u32).collect(),
+                        data: cropped,
+                    }
+                })
+                .collect();
+
+            Ok(RknnBucketedRun {
+                shape_set,
+                input_shapes,
+                outputs,
+            })
         }
 
-        pub fn input_attrs(&self) -> Result<Vec<RknnTensorAttr>, Error> {
-            let io_num = self.io_num()?;
-            self.query_tensor_attrs(
-                rknn_sys::_rknn_query_cmd_RKNN_QUERY_INPUT_ATTR,
-                io_num.n_input,
-            )
+        /// Weight, internal and DMA memory used by this context.
+        ///
+        /// `RKNN_QUERY_DEVICE_MEM_INFO` is not wrapped: `rknn_api.h` does not define the
+        /// structure it returns, so this is the supported way to report memory usage.
+        pub fn mem_size(&self) -> Result<RknnMemSize, Error> {
+            self.guard.mem_size()
         }
 
-        pub fn output_attrs(&self) -> Result<Vec<RknnTensorAttr>, Error> {
-            let io_num = self.io_num()?;
-            self.query_tensor_attrs(
-                rknn_sys::_rknn_query_cmd_RKNN_QUERY_OUTPUT_ATTR,
-                io_num.n_output,
-            )
+        /// The custom string stored in the model at conversion time
+        /// (`RKNN_QUERY_CUSTOM_STRING`), or `None` if the model has none.
+        pub fn custom_string(&self) -> Result<Option<String>, Error> {
+            self.guard.custom_string()
         }
 
-        pub fn model_info(&self) -> Result<RknnModelInfo, Error> {
-            let io_num = self.io_num()?;
-            let input_attrs = self.query_tensor_attrs(
-                rknn_sys::_rknn_query_cmd_RKNN_QUERY_INPUT_ATTR,
-                io_num.n_input,
-            )?;
-            let output_attrs = self.query_tensor_attrs(
-                rknn_sys::_rknn_query_cmd_RKNN_QUERY_OUTPUT_ATTR,
-                io_num.n_output,
-            )?;
-            Ok(RknnModelInfo {
-                io_num,
-                input_attrs,
-                output_attrs,
-            })
+        /// Deserialize the custom string as JSON, e.g. class labels or normalization
+        /// constants written by the conversion pipeline. Returns `None` if the model has
+        /// no custom string.
+        #[cfg(feature = "serde")]
+        pub fn custom_metadata<T: serde::de::DeserializeOwned>(&self) -> Result<Option<T>, Error> {
+            self.guard.custom_metadata()
         }
 
         pub fn create_mem<'a>(&'a self, size: u32) -> Result<RknnTensorMemory<'a>, Error> {
             let raw = unsafe { rknn_sys::rknn_create_mem(self.context, size) };
             if raw.is_null() {
-                return Err(Error("rknn_create_mem failed.".to_string()));
+                return Err(Error::AllocationFailed {
+                    api: "rknn_create_mem",
+                });
             }
             Ok(RknnTensorMemory {
                 context: self.context,
@@ -757,7 +2998,9 @@ pub mod prelude {
         ) -> Result<RknnTensorMemory<'a>, Error> {
             let raw = unsafe { rknn_sys::rknn_create_mem2(self.context, size, alloc_flags) };
             if raw.is_null() {
-                return Err(Error("rknn_create_mem2 failed.".to_string()));
+                return Err(Error::AllocationFailed {
+                    api: "rknn_create_mem2",
+                });
             }
             Ok(RknnTensorMemory {
                 context: self.context,
@@ -774,7 +3017,7 @@ pub mod prelude {
         ///
         /// If successful, returns `Ok(()`; otherwise, returns an `Error`.
         pub fn info(&self) -> Result<(), Error> {
-            let info = self.model_info()?;
+            let info = self.model_info();
             println!("{:?}", info.io_num);
             for attr in &info.input_attrs {
                 println!("input: {:?}", attr);
@@ -785,40 +3028,28 @@ pub mod prelude {
             Ok(())
         }
 
-        /// Get the model's output (raw version).
-        ///
-        /// This method returns raw output data (zero-copy) and delegates resource management to `RknnOutput<T>`.
-        /// The returned `RknnOutput` automatically releases resources when dropped.
-        ///
-        /// # Arguments
-        ///
-        /// * `index` - Output tensor index (default 0 for single-output models).
-        /// * `want_float` - If true, ask the runtime to convert the output to float32.
-        ///
-        /// # Returns
-        ///
-        /// If successful, returns a `RknnOutput<'a, T>`; otherwise, returns an `Error`.
-        pub fn outputs_get_by_index<'a, T: Pod + Copy + 'static>(
-            &'a self,
-            index: u32,
-            want_float: bool,
-        ) -> Result<RknnOutput<'a, T>, Error> {
+        /// Call `rknn_outputs_get` for every output of the model.
+        fn raw_outputs_get(
+            &self,
+            want_float: &[bool],
+        ) -> Result<Vec<rknn_sys::rknn_output>, Error> {
             // IMPORTANT: The RKNN 2.3.x runtime internally iterates ALL model outputs
             // regardless of the n_outputs argument. We must allocate a full array
             // (size = n_model_output) so that the runtime never reads past the end.
-            let n_total = self.io_num()?.n_output;
-            if index >= n_total {
-                return Err(Error(format!(
-                    "output index {} out of range (model has {} outputs)",
-                    index, n_total
-                )));
+            let n_total = self.info.io_num.n_output;
+            if want_float.len() != n_total as usize {
+                return Err(Error::SizeMismatch {
+                    what: "number of want_float settings".to_string(),
+                    expected: n_total as usize,
+                    actual: want_float.len(),
+                });
             }
 
             // Zero all structs (including padding) to ensure the runtime reads clean data.
             let mut all_raws: Vec<rknn_sys::rknn_output> = (0..n_total)
                 .map(|i| {
                     let mut o: rknn_sys::rknn_output = unsafe { mem::zeroed() };
-                    o.want_float = if want_float { 1 } else { 0 };
+                    o.want_float = if want_float[i as usize] { 1 } else { 0 };
                     o.is_prealloc = 0;
                     o.index = i;
                     o.buf = std::ptr::null_mut();
@@ -836,43 +3067,203 @@ pub mod prelude {
                 )
             };
             if result != 0 {
-                return rkerr!("rknn_outputs_get failed.", result);
+                return rkerr!("rknn_outputs_get", result);
             }
+            Ok(all_raws)
+        }
 
-            let desired = &all_raws[index as usize];
-            if desired.buf.is_null() {
-                // Release all before returning error
-                unsafe {
-                    rknn_sys::rknn_outputs_release(
-                        self.context,
-                        n_total,
-                        all_raws.as_mut_ptr(),
-                    );
+        /// Get all outputs of the model with a single `rknn_outputs_get` call.
+        ///
+        /// The returned [`RknnOutputs`] gives zero-copy views of every output and releases
+        /// them together when dropped.
+        ///
+        /// # Arguments
+        ///
+        /// * `want_float` - If true, ask the runtime to convert every output to float32.
+        ///
+        /// # Examples
+        ///
+        /// ```no_run
+        /// use rknn_rs::prelude::*;
+        ///
+        /// fn main() -> Result<(), Error> {
+        ///     let rknn = Rknn::new("yolo.rknn")?;
+        ///     rknn.run()?;
+        ///     let outputs = rknn.outputs_get_all(true)?;
+        ///     for i in 0..outputs.len() {
+        ///         let head = outputs.get::<f32>(i)?;
+        ///         println!("head {}: {} values", i, head.len());
+        ///     }
+        ///     Ok(())
+        /// }
+        /// ```
+        pub fn outputs_get_all(&self, want_float: bool) -> Result<RknnOutputs<'_>, Error> {
+            let want_float = vec![want_float; self.info.io_num.n_output as usize];
+            self.outputs_get_all_with(&want_float)
+        }
+
+        /// Same as [`Rknn::outputs_get_all`], with a `want_float` setting per output, e.g.
+        /// to read some heads as raw int8 and others as float32.
+        pub fn outputs_get_all_with(&self, want_float: &[bool]) -> Result<RknnOutputs<'_>, Error> {
+            Ok(RknnOutputs {
+                context: self.context,
+                all_raws: self.raw_outputs_get(want_float)?,
+                attrs: self.current_output_attrs(),
+            })
+        }
+
+        /// Write outputs directly into caller-owned buffers (`is_prealloc = 1`).
+        ///
+        /// Avoids the per-frame allocation of output buffers by the runtime, and the
+        /// results do not borrow `self`. Outputs without a slot are fetched and released
+        /// again within this call.
+        ///
+        /// # Examples
+        ///
+        /// ```no_run
+        /// use rknn_rs::prelude::*;
+        ///
+        /// fn main() -> Result<(), Error> {
+        ///     let rknn = Rknn::new("model.rknn")?;
+        ///     let mut scores = vec![0f32; rknn.output_attrs()[0].n_elems as usize];
+        ///     loop {
+        ///         rknn.run()?;
+        ///         rknn.outputs_get_into(&mut [OutputSlot::new(0, &mut scores, true)])?;
+        ///     }
+        /// }
+        /// ```
+        pub fn outputs_get_into(&self, slots: &mut [OutputSlot<'_>]) -> Result<(), Error> {
+            let attrs = self.current_output_attrs();
+            let n_total = self.info.io_num.n_output;
+            let mut all_raws: Vec<rknn_sys::rknn_output> = (0..n_total)
+                .map(|i| {
+                    let mut o: rknn_sys::rknn_output = unsafe { mem::zeroed() };
+                    o.index = i;
+                    o
+                })
+                .collect();
+            for slot in slots.iter_mut() {
+                let attr = attrs.get(slot.index).ok_or(Error::IndexOutOfRange {
+                    kind: "output",
+                    index: slot.index,
+                    count: attrs.len(),
+                })?;
+                let raw = &mut all_raws[slot.index];
+                if raw.is_prealloc != 0 {
+                    return Err(Error::InvalidArgument(format!(
+                        "Output {} has more than one slot",
+                        slot.index
+                    )));
                 }
-                return Err(Error(format!(
-                    "rknn_outputs_get returned null buffer for output index {}",
-                    index
-                )));
+                let output_type = if slot.want_float {
+                    RknnTensorType::Float32
+                } else {
+                    attr.type_
+                };
+                if !element_matches(output_type, slot.type_) {
+                    return Err(Error::TypeMismatch {
+                        what: format!("output {}", slot.index),
+                        expected: output_type,
+                        actual: slot.type_,
+                    });
+                }
+                let needed = if slot.want_float {
+                    attr.n_elems as usize * mem::size_of::<f32>()
+                } else {
+                    attr.size as usize
+                };
+                if slot.buf.len() < needed {
+                    return Err(Error::SizeMismatch {
+                        what: format!("bytes in the buffer for output '{}'", attr.name),
+                        expected: needed,
+                        actual: slot.buf.len(),
+                    });
+                }
+                raw.want_float = if slot.want_float { 1 } else { 0 };
+                raw.is_prealloc = 1;
+                raw.buf = slot.buf.as_mut_ptr() as *mut c_void;
+                raw.size = needed as u32;
+            }
+
+            let result = unsafe {
+                rknn_sys::rknn_outputs_get(
+                    self.context,
+                    n_total,
+                    all_raws.as_mut_ptr(),
+                    std::ptr::null_mut(),
+                )
+            };
+            if result != 0 {
+                return rkerr!("rknn_outputs_get", result);
             }
-            let element_size = mem::size_of::<T>();
-            let num_elements = desired.size as usize / element_size;
-            let t_slice =
-                unsafe { slice::from_raw_parts(desired.buf as *const T, num_elements) };
+            // Frees the buffers of the outputs without a slot; preallocated ones are untouched.
+            unsafe {
+                rknn_sys::rknn_outputs_release(self.context, n_total, all_raws.as_mut_ptr());
+            }
+            Ok(())
+        }
 
+        /// Get the model's output (raw version).
+        ///
+        /// This method returns raw output data (zero-copy) and delegates resource management to `RknnOutput<T>`.
+        /// The returned `RknnOutput` automatically releases resources when dropped. To read
+        /// more than one output, use [`Rknn::outputs_get_all`] instead.
+        ///
+        /// # Arguments
+        ///
+        /// * `index` - Output tensor index (default 0 for single-output models).
+        /// * `want_float` - If true, ask the runtime to convert the output to float32.
+        ///
+        /// # Returns
+        ///
+        /// If successful, returns a `RknnOutput<'a, T>`; otherwise, returns an `Error`.
+        /// `T` must match the output type, which is `f32` when `want_float` is set. `u8`
+        /// also reads `Boolean` and `Int4` outputs, `u16` reads `Float16`/`BFloat16` outputs.
+        pub fn outputs_get_by_index<'a, T: TensorElement>(
+            &'a self,
+            index: u32,
+            want_float: bool,
+        ) -> Result<RknnOutput<'a, T>, Error> {
+            let mut outputs = self.outputs_get_all(want_float)?;
+            let memory = outputs.slice::<T>(index as usize)?;
+            let (ptr, len) = (memory.as_ptr(), memory.len());
+            // Move the ownership of the runtime buffers into the returned output.
+            let all_raws = mem::take(&mut outputs.all_raws);
             Ok(RknnOutput {
                 context: self.context,
-                memory: t_slice,
+                memory: unsafe { slice::from_raw_parts(ptr, len) },
+                attr: &self.current_output_attrs()[index as usize],
                 all_raws,
             })
         }
 
+        /// Get a raw quantized output (`want_float = false`) with its quantization
+        /// parameters, see [`QuantizedOutput`].
+        pub fn outputs_get_quantized<T: QuantizedElement>(
+            &self,
+            index: u32,
+        ) -> Result<QuantizedOutput<'_, T>, Error> {
+            Ok(self.outputs_get_by_index(index, false)?.into_quantized())
+        }
+
+        /// Get an output by tensor name instead of index.
+        ///
+        /// Same as [`Rknn::outputs_get_by_index`], with the index resolved through
+        /// [`Rknn::output_index`].
+        pub fn output_by_name<'a, T: TensorElement>(
+            &'a self,
+            name: &str,
+            want_float: bool,
+        ) -> Result<RknnOutput<'a, T>, Error> {
+            let index = self.output_index(name)?;
+            self.outputs_get_by_index(index as u32, want_float)
+        }
+
         /// Get the model's first output as float32.
         ///
         /// Convenience wrapper around [`outputs_get_by_index`] for single-output models.
         /// Asks the runtime to convert the output to float32 (`want_float = true`).
-        pub fn outputs_get<'a, T: Pod + Copy + 'static>(
-            &'a self,
-        ) -> Result<RknnOutput<'a, T>, Error> {
+        pub fn outputs_get<'a, T: TensorElement>(&'a self) -> Result<RknnOutput<'a, T>, Error> {
             self.outputs_get_by_index(0, true)
         }
     }